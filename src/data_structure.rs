@@ -1,3 +1,21 @@
 pub mod fenwick_tree;
 pub mod disjoint_set;
-pub mod lazy_segtree;
\ No newline at end of file
+pub mod lazy_segtree;
+pub mod weighted_disjoint_set;
+pub mod rollback_disjoint_set;
+pub mod offline_dynamic_connectivity;
+pub mod partially_persistent_disjoint_set;
+pub mod compressed_fenwick_tree_2d;
+pub mod segtree;
+pub mod algebra;
+pub mod segtree_beats;
+pub mod persistent_segtree;
+pub mod dynamic_segtree;
+pub mod dual_segtree;
+pub mod li_chao_tree;
+pub mod convex_hull_trick;
+pub mod bit_vector;
+pub mod wavelet_matrix;
+pub mod merge_sort_tree;
+pub mod sparse_table;
+pub mod implicit_treap;
\ No newline at end of file
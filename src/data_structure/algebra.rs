@@ -0,0 +1,511 @@
+use crate::math::modint::ModInt;
+
+// S and F only need Clone, values are passed by reference
+pub trait Monoid {
+    type S: Clone;
+    fn identity(&self) -> Self::S;
+    fn op(&self, a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+// composition(f, g) applies g first, then f
+pub trait MapMonoid: Monoid {
+    type F: Clone;
+    fn identity_map(&self) -> Self::F;
+    fn mapping(&self, f: &Self::F, x: &Self::S) -> Self::S;
+    fn composition(&self, f: &Self::F, g: &Self::F) -> Self::F;
+}
+
+// closure constructors (Segtree, LazySegtree, ImplicitTreap, SparseTable) take Copy values by value
+// for Clone values implement Monoid or MapMonoid and use with_monoid / from_monoid
+// the sparse tables only have the closure form
+
+// Monoid made of a closure, for one-off algebras over Copy values
+pub struct ClosureMonoid<S, F> {
+    op: F,
+    e: S,
+}
+
+impl<S, F> ClosureMonoid<S, F>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    pub fn new(op: F, e: S) -> Self {
+        Self { op, e }
+    }
+}
+
+impl<S, F> Monoid for ClosureMonoid<S, F>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    type S = S;
+
+    fn identity(&self) -> S {
+        self.e
+    }
+
+    fn op(&self, a: &S, b: &S) -> S {
+        (self.op)(*a, *b)
+    }
+}
+
+// MapMonoid made of closures, for one-off algebras over Copy values
+pub struct ClosureMapMonoid<S, T, F, G, H> {
+    op: F,
+    e: S,
+    mapping: G,
+    composition: H,
+    id: T,
+}
+
+impl<S, T, F, G, H> ClosureMapMonoid<S, T, F, G, H>
+where
+    S: Copy,
+    T: Copy,
+    F: Fn(S, S) -> S,
+    G: Fn(T, S) -> S,
+    H: Fn(T, T) -> T
+{
+    pub fn new(op: F, e: S, mapping: G, composition: H, id: T) -> Self {
+        Self {
+            op,
+            e,
+            mapping,
+            composition,
+            id,
+        }
+    }
+}
+
+impl<S, T, F, G, H> Monoid for ClosureMapMonoid<S, T, F, G, H>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    type S = S;
+
+    fn identity(&self) -> S {
+        self.e
+    }
+
+    fn op(&self, a: &S, b: &S) -> S {
+        (self.op)(*a, *b)
+    }
+}
+
+impl<S, T, F, G, H> MapMonoid for ClosureMapMonoid<S, T, F, G, H>
+where
+    S: Copy,
+    T: Copy,
+    F: Fn(S, S) -> S,
+    G: Fn(T, S) -> S,
+    H: Fn(T, T) -> T
+{
+    type F = T;
+
+    fn identity_map(&self) -> T {
+        self.id
+    }
+
+    fn mapping(&self, f: &T, x: &S) -> S {
+        (self.mapping)(*f, *x)
+    }
+
+    fn composition(&self, f: &T, g: &T) -> T {
+        (self.composition)(*f, *g)
+    }
+}
+
+// range add, range min
+pub struct RangeAddMin;
+
+impl Monoid for RangeAddMin {
+    type S = i64;
+
+    fn identity(&self) -> i64 {
+        i64::MAX
+    }
+
+    fn op(&self, a: &i64, b: &i64) -> i64 {
+        *a.min(b)
+    }
+}
+
+impl MapMonoid for RangeAddMin {
+    type F = i64;
+
+    fn identity_map(&self) -> i64 {
+        0
+    }
+
+    fn mapping(&self, f: &i64, x: &i64) -> i64 {
+        if *x == i64::MAX {*x} else {x + f}
+    }
+
+    fn composition(&self, f: &i64, g: &i64) -> i64 {
+        f + g
+    }
+}
+
+// range add, range max
+pub struct RangeAddMax;
+
+impl Monoid for RangeAddMax {
+    type S = i64;
+
+    fn identity(&self) -> i64 {
+        i64::MIN
+    }
+
+    fn op(&self, a: &i64, b: &i64) -> i64 {
+        *a.max(b)
+    }
+}
+
+impl MapMonoid for RangeAddMax {
+    type F = i64;
+
+    fn identity_map(&self) -> i64 {
+        0
+    }
+
+    fn mapping(&self, f: &i64, x: &i64) -> i64 {
+        if *x == i64::MIN {*x} else {x + f}
+    }
+
+    fn composition(&self, f: &i64, g: &i64) -> i64 {
+        f + g
+    }
+}
+
+// range add, range sum
+// S = (sum, length), so an element x is (x, 1)
+pub struct RangeAddSum;
+
+impl Monoid for RangeAddSum {
+    type S = (i64, i64);
+
+    fn identity(&self) -> (i64, i64) {
+        (0, 0)
+    }
+
+    fn op(&self, a: &(i64, i64), b: &(i64, i64)) -> (i64, i64) {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+impl MapMonoid for RangeAddSum {
+    type F = i64;
+
+    fn identity_map(&self) -> i64 {
+        0
+    }
+
+    fn mapping(&self, f: &i64, x: &(i64, i64)) -> (i64, i64) {
+        (x.0 + f * x.1, x.1)
+    }
+
+    fn composition(&self, f: &i64, g: &i64) -> i64 {
+        f + g
+    }
+}
+
+// range assign, range sum
+// S = (sum, length), so an element x is (x, 1)
+pub struct RangeAssignSum;
+
+impl Monoid for RangeAssignSum {
+    type S = (i64, i64);
+
+    fn identity(&self) -> (i64, i64) {
+        (0, 0)
+    }
+
+    fn op(&self, a: &(i64, i64), b: &(i64, i64)) -> (i64, i64) {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+impl MapMonoid for RangeAssignSum {
+    type F = Option<i64>;
+
+    fn identity_map(&self) -> Option<i64> {
+        None
+    }
+
+    fn mapping(&self, f: &Option<i64>, x: &(i64, i64)) -> (i64, i64) {
+        match f {
+            Some(v) => (v * x.1, x.1),
+            None => *x,
+        }
+    }
+
+    fn composition(&self, f: &Option<i64>, g: &Option<i64>) -> Option<i64> {
+        f.or(*g)
+    }
+}
+
+// x -> b * x + c, range sum
+// S = (sum, length), F = (b, c)
+pub struct RangeAffineSum<const MOD: usize>;
+
+impl<const MOD: usize> Monoid for RangeAffineSum<MOD> {
+    type S = (ModInt<MOD>, ModInt<MOD>);
+
+    fn identity(&self) -> Self::S {
+        (ModInt::new(0), ModInt::new(0))
+    }
+
+    fn op(&self, a: &Self::S, b: &Self::S) -> Self::S {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+impl<const MOD: usize> MapMonoid for RangeAffineSum<MOD> {
+    type F = (ModInt<MOD>, ModInt<MOD>);
+
+    fn identity_map(&self) -> Self::F {
+        (ModInt::new(1), ModInt::new(0))
+    }
+
+    fn mapping(&self, f: &Self::F, x: &Self::S) -> Self::S {
+        (f.0 * x.0 + f.1 * x.1, x.1)
+    }
+
+    fn composition(&self, f: &Self::F, g: &Self::F) -> Self::F {
+        (f.0 * g.0, f.0 * g.1 + f.1)
+    }
+}
+
+// range flip of 0/1, range count of ones
+// S = (ones, length), so an element b is (b, 1)
+pub struct RangeFlipCount;
+
+impl Monoid for RangeFlipCount {
+    type S = (usize, usize);
+
+    fn identity(&self) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn op(&self, a: &(usize, usize), b: &(usize, usize)) -> (usize, usize) {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+impl MapMonoid for RangeFlipCount {
+    type F = bool;
+
+    fn identity_map(&self) -> bool {
+        false
+    }
+
+    fn mapping(&self, f: &bool, x: &(usize, usize)) -> (usize, usize) {
+        if *f {(x.1 - x.0, x.1)} else {*x}
+    }
+
+    fn composition(&self, f: &bool, g: &bool) -> bool {
+        f ^ g
+    }
+}
+
+// maximum sum of a non-empty subarray
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MaxSubarray {
+    pub sum: i64,
+    pub prefix: i64,
+    pub suffix: i64,
+    pub best: i64,
+    pub len: i64,
+}
+
+impl MaxSubarray {
+    pub fn new(x: i64) -> Self {
+        Self {
+            sum: x,
+            prefix: x,
+            suffix: x,
+            best: x,
+            len: 1,
+        }
+    }
+}
+
+// range assign, range max subarray sum
+pub struct RangeAssignMaxSubarray;
+
+impl Monoid for RangeAssignMaxSubarray {
+    type S = MaxSubarray;
+
+    fn identity(&self) -> MaxSubarray {
+        MaxSubarray {
+            sum: 0,
+            prefix: i64::MIN,
+            suffix: i64::MIN,
+            best: i64::MIN,
+            len: 0,
+        }
+    }
+
+    fn op(&self, a: &MaxSubarray, b: &MaxSubarray) -> MaxSubarray {
+        MaxSubarray {
+            sum: a.sum + b.sum,
+            prefix: a.prefix.max(a.sum.saturating_add(b.prefix)),
+            suffix: b.suffix.max(b.sum.saturating_add(a.suffix)),
+            best: a.best.max(b.best).max(a.suffix.saturating_add(b.prefix)),
+            len: a.len + b.len,
+        }
+    }
+}
+
+impl MapMonoid for RangeAssignMaxSubarray {
+    type F = Option<i64>;
+
+    fn identity_map(&self) -> Option<i64> {
+        None
+    }
+
+    fn mapping(&self, f: &Option<i64>, x: &MaxSubarray) -> MaxSubarray {
+        match f {
+            Some(v) if x.len > 0 => {
+                let m = if *v > 0 {v * x.len} else {*v};
+                MaxSubarray {
+                    sum: v * x.len,
+                    prefix: m,
+                    suffix: m,
+                    best: m,
+                    len: x.len,
+                }
+            }
+            _ => *x,
+        }
+    }
+
+    fn composition(&self, f: &Option<i64>, g: &Option<i64>) -> Option<i64> {
+        f.or(*g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::lazy_segtree::LazySegtree;
+    use crate::util::rng::Rng;
+
+    const N: usize = 30;
+    const Q: usize = 2000;
+
+    #[test]
+    fn test_add_min_max_sum() {
+        let mut rng = Rng::new();
+        let mut a = (0..N).map(|_| rng.int(-100, 101)).collect::<Vec<i64>>();
+        let mut min = LazySegtree::from_monoid(a.clone(), RangeAddMin);
+        let mut max = LazySegtree::from_monoid(a.clone(), RangeAddMax);
+        let mut sum = LazySegtree::from_monoid(a.iter().map(|&x| (x, 1)).collect(), RangeAddSum);
+        for _ in 0..Q {
+            let (l, r) = rng.range(N);
+            if rng.next_usize().is_multiple_of(2) {
+                let f = rng.int(-100, 101);
+                min.apply_range(l, r, f);
+                max.apply_range(l, r, f);
+                sum.apply_range(l, r, f);
+                a[l..r].iter_mut().for_each(|x| *x += f);
+            }
+            else {
+                assert_eq!(min.prod(l, r), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                assert_eq!(max.prod(l, r), a[l..r].iter().copied().max().unwrap_or(i64::MIN));
+                assert_eq!(sum.prod(l, r), (a[l..r].iter().sum(), (r - l) as i64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_assign_sum() {
+        let mut rng = Rng::new();
+        let mut a = (0..N).map(|_| rng.int(-100, 101)).collect::<Vec<i64>>();
+        let mut st = LazySegtree::from_monoid(a.iter().map(|&x| (x, 1)).collect(), RangeAssignSum);
+        for _ in 0..Q {
+            let (l, r) = rng.range(N);
+            if rng.next_usize().is_multiple_of(2) {
+                let f = rng.int(-100, 101);
+                st.apply_range(l, r, Some(f));
+                a[l..r].iter_mut().for_each(|x| *x = f);
+            }
+            else {
+                assert_eq!(st.prod(l, r).0, a[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_affine_sum() {
+        const MOD: usize = 998244353;
+        let mut rng = Rng::new();
+        let mut a = (0..N).map(|_| rng.next_usize() % MOD).collect::<Vec<usize>>();
+        let mut st = LazySegtree::from_monoid(
+            a.iter().map(|&x| (ModInt::new(x), ModInt::new(1))).collect(),
+            RangeAffineSum::<MOD>,
+        );
+        for _ in 0..Q {
+            let (l, r) = rng.range(N);
+            if rng.next_usize().is_multiple_of(2) {
+                let (b, c) = (rng.next_usize() % MOD, rng.next_usize() % MOD);
+                st.apply_range(l, r, (ModInt::new(b), ModInt::new(c)));
+                a[l..r].iter_mut().for_each(|x| *x = (b * *x + c) % MOD);
+            }
+            else {
+                let s = a[l..r].iter().fold(0, |s, x| (s + x) % MOD);
+                assert_eq!(st.prod(l, r).0.val() % MOD, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flip_count() {
+        let mut rng = Rng::new();
+        let mut a = (0..N).map(|_| rng.next_usize() % 2).collect::<Vec<usize>>();
+        let mut st = LazySegtree::from_monoid(a.iter().map(|&x| (x, 1)).collect(), RangeFlipCount);
+        for _ in 0..Q {
+            let (l, r) = rng.range(N);
+            if rng.next_usize().is_multiple_of(2) {
+                st.apply_range(l, r, true);
+                a[l..r].iter_mut().for_each(|x| *x ^= 1);
+            }
+            else {
+                assert_eq!(st.prod(l, r).0, a[l..r].iter().sum::<usize>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_subarray() {
+        let mut rng = Rng::new();
+        let mut a = (0..N).map(|_| rng.int(-100, 101)).collect::<Vec<i64>>();
+        let mut st = LazySegtree::from_monoid(a.iter().map(|&x| MaxSubarray::new(x)).collect(), RangeAssignMaxSubarray);
+        for _ in 0..Q {
+            let (l, r) = rng.range(N);
+            match rng.next_usize() % 3 {
+                0 => {
+                    let f = rng.int(-100, 101);
+                    st.apply_range(l, r, Some(f));
+                    a[l..r].iter_mut().for_each(|x| *x = f);
+                }
+                1 if l < N => {
+                    let x = rng.int(-100, 101);
+                    st.set(l, MaxSubarray::new(x));
+                    a[l] = x;
+                }
+                _ => {
+                    let best = (l..r)
+                        .flat_map(|i| (i + 1..=r).map(move |j| (i, j)))
+                        .map(|(i, j)| a[i..j].iter().sum::<i64>())
+                        .max()
+                        .unwrap_or(i64::MIN);
+                    assert_eq!(st.prod(l, r).best, best);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// static bit vector with O(1) rank
+// two-level rank directory: a counter per 512-bit block and a u16 per word relative to its block
+pub struct BitVector {
+    n: usize,
+    words: Vec<u64>,
+    // large[i]: the number of ones in words[..8 * i]
+    large: Vec<usize>,
+    // small[i]: the number of ones in words[8 * (i / 8)..i]
+    small: Vec<u16>,
+}
+
+impl BitVector {
+    pub fn new(bits: &[bool]) -> Self {
+        let n = bits.len();
+        let mut words = vec![0u64; n / 64 + 1];
+        for (i, &b) in bits.iter().enumerate() {
+            if b {
+                words[i >> 6] |= 1 << (i & 63);
+            }
+        }
+        let mut large = Vec::with_capacity(words.len() / 8 + 1);
+        let mut small = Vec::with_capacity(words.len());
+        let (mut total, mut block) = (0, 0);
+        for (i, w) in words.iter().enumerate() {
+            if i & 7 == 0 {
+                total += block as usize;
+                block = 0;
+                large.push(total);
+            }
+            small.push(block);
+            block += w.count_ones() as u16;
+        }
+        Self { n, words, large, small }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.n);
+        self.words[i >> 6] >> (i & 63) & 1 == 1
+    }
+
+    // the number of ones in [0, r)
+    pub fn rank1(&self, r: usize) -> usize {
+        assert!(r <= self.n);
+        let mask = (1u64 << (r & 63)) - 1;
+        let w = r >> 6;
+        self.large[w >> 3] + self.small[w] as usize + (self.words[w] & mask).count_ones() as usize
+    }
+
+    // the number of zeros in [0, r)
+    pub fn rank0(&self, r: usize) -> usize {
+        r - self.rank1(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_bit_vector() {
+        let mut rng = Rng::new();
+        for n in [0, 1, 63, 64, 65, 200, 511, 512, 513, 2000] {
+            let bits = (0..n).map(|_| rng.next_usize().is_multiple_of(3)).collect::<Vec<bool>>();
+            let bv = BitVector::new(&bits);
+            assert_eq!(bv.len(), n);
+            let mut ones = 0;
+            for (r, &b) in bits.iter().enumerate() {
+                assert_eq!(bv.rank1(r), ones);
+                assert_eq!(bv.rank0(r), r - ones);
+                assert_eq!(bv.get(r), b);
+                ones += b as usize;
+            }
+            assert_eq!(bv.rank1(n), ones);
+            assert_eq!(bv.rank0(n), n - ones);
+        }
+    }
+}
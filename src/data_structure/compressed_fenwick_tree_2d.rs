@@ -0,0 +1,114 @@
+use std::ops::{Add, Sub, AddAssign, Range};
+use crate::data_structure::fenwick_tree::FenwickTree;
+use crate::util::binary_search::BinarySearch;
+
+// point add / rectangle sum on points given in advance
+// O(n log n) memory, O(log^2 n) per operation
+pub struct CompressedFenwickTree2D<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    xs: Vec<i64>,
+    ys: Vec<Vec<i64>>,
+    data: Vec<FenwickTree<T>>,
+    e: T,
+}
+
+impl<T> CompressedFenwickTree2D<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    pub fn new(points: &[(i64, i64)], e: T) -> Self {
+        let mut xs = points.iter().map(|&(x, _)| x).collect::<Vec<i64>>();
+        xs.sort();
+        xs.dedup();
+        let n = xs.len();
+        let mut ys = vec![Vec::new(); n + 1];
+        for &(x, y) in points {
+            let mut k = xs.lower_bound(&x) + 1;
+            while k <= n {
+                ys[k].push(y);
+                k += k & k.wrapping_neg();
+            }
+        }
+        for v in ys.iter_mut() {
+            v.sort();
+            v.dedup();
+        }
+        let data = ys.iter().map(|v| FenwickTree::new(v.len(), e)).collect();
+        Self {
+            xs,
+            ys,
+            data,
+            e,
+        }
+    }
+
+    // (x, y) must be one of the given points
+    pub fn add(&mut self, x: i64, y: i64, w: T) {
+        let i = self.xs.lower_bound(&x);
+        assert!(i < self.xs.len() && self.xs[i] == x);
+        let mut k = i + 1;
+        while k < self.ys.len() {
+            let j = self.ys[k].lower_bound(&y);
+            assert!(j < self.ys[k].len() && self.ys[k][j] == y);
+            self.data[k].add(j, w);
+            k += k & k.wrapping_neg();
+        }
+    }
+
+    // [x.start, x.end) * [y.start, y.end)
+    pub fn sum(&self, x: Range<i64>, y: Range<i64>) -> T {
+        if x.start >= x.end || y.start >= y.end {
+            return self.e;
+        }
+        let l = self.xs.lower_bound(&x.start);
+        let r = self.xs.lower_bound(&x.end);
+        self.prefix(r, &y) - self.prefix(l, &y)
+    }
+
+    fn prefix(&self, mut k: usize, y: &Range<i64>) -> T {
+        let mut res = self.e;
+        while k != 0 {
+            let a = self.ys[k].lower_bound(&y.start);
+            let b = self.ys[k].lower_bound(&y.end);
+            res += self.data[k].sum(a..b);
+            k &= k - 1;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn compressed_fw_2d_test() {
+        let mut rng = Rng::new();
+        let coord = |r: u64| (r % 41) as i64 * 50_000_000_000_000_000 - 1_000_000_000_000_000_000;
+        let points = (0..60).map(|_| (coord(rng.next_u64()), coord(rng.next_u64()))).collect::<Vec<_>>();
+        let mut bt = CompressedFenwickTree2D::new(&points, 0i64);
+        let mut w = vec![0i64; points.len()];
+        for _ in 0..1000 {
+            if rng.next_u64().is_multiple_of(2) {
+                let i = rng.next_usize() % points.len();
+                let v = (rng.next_u64() % 100) as i64 - 50;
+                bt.add(points[i].0, points[i].1, v);
+                w[i] += v;
+            }
+            else {
+                let (x1, x2) = (coord(rng.next_u64()), coord(rng.next_u64()) + 1);
+                let (y1, y2) = (coord(rng.next_u64()), coord(rng.next_u64()) + 1);
+                let s = points
+                    .iter()
+                    .zip(w.iter())
+                    .filter(|&(&(x, y), _)| x1 <= x && x < x2 && y1 <= y && y < y2)
+                    .map(|(_, &v)| v)
+                    .sum::<i64>();
+                assert_eq!(bt.sum(x1..x2, y1..y2), s);
+            }
+        }
+    }
+}
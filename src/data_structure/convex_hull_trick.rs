@@ -0,0 +1,180 @@
+use crate::util::binary_search::BinarySearch;
+
+// lines y = a * x + b added in sorted slope order
+// min: slopes must be non-increasing, max: slopes must be non-decreasing
+// lines are kept negated for max in i128, every i64 coefficient is safe
+pub struct ConvexHullTrick {
+    sign: i128,
+    lines: Vec<(i128, i128)>,
+    // cross[i]: the smallest integer x where lines[i + 1] is at least as good as lines[i]
+    cross: Vec<i64>,
+    ptr: usize,
+}
+
+impl Default for ConvexHullTrick {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConvexHullTrick {
+    pub fn new() -> Self {
+        Self {
+            sign: 1,
+            lines: Vec::new(),
+            cross: Vec::new(),
+            ptr: 0,
+        }
+    }
+
+    pub fn new_max() -> Self {
+        Self {
+            sign: -1,
+            ..Self::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let (a, b) = (a as i128 * self.sign, b as i128 * self.sign);
+        if let Some(&(la, lb)) = self.lines.last() {
+            assert!(la >= a, "slopes must be sorted");
+            if la == a {
+                if lb <= b {
+                    return;
+                }
+                self.pop();
+            }
+        }
+        while self.lines.len() >= 2 {
+            let n = self.lines.len();
+            if Self::is_unnecessary(self.lines[n - 2], self.lines[n - 1], (a, b)) {
+                self.pop();
+            }
+            else {
+                break;
+            }
+        }
+        if let Some(&last) = self.lines.last() {
+            self.cross.push(Self::cross_point(last, (a, b)));
+        }
+        self.lines.push((a, b));
+    }
+
+    // O(log n)
+    pub fn query(&self, x: i64) -> i128 {
+        assert!(!self.lines.is_empty());
+        let i = self.cross.upper_bound(&x);
+        Self::eval(self.lines[i], x) * self.sign
+    }
+
+    // x must be non-decreasing over the calls, amortized O(1)
+    pub fn query_monotone(&mut self, x: i64) -> i128 {
+        assert!(!self.lines.is_empty());
+        self.ptr = self.ptr.min(self.lines.len() - 1);
+        while self.ptr < self.cross.len() && self.cross[self.ptr] <= x {
+            self.ptr += 1;
+        }
+        Self::eval(self.lines[self.ptr], x) * self.sign
+    }
+
+    fn pop(&mut self) {
+        self.lines.pop();
+        self.cross.pop();
+    }
+
+    fn eval((a, b): (i128, i128), x: i64) -> i128 {
+        a * x as i128 + b
+    }
+
+    // l1.a > l2.a > l3.a, l2 is never strictly below both l1 and l3 at an integer x
+    // compared through the integer cross points, a product of differences could overflow i128
+    fn is_unnecessary(l1: (i128, i128), l2: (i128, i128), l3: (i128, i128)) -> bool {
+        Self::cross_point(l2, l3) <= Self::cross_point(l1, l2)
+    }
+
+    // ceil((b2 - b1) / (a1 - a2)) for a1 > a2, clamped to i64
+    fn cross_point(l1: (i128, i128), l2: (i128, i128)) -> i64 {
+        let num = l2.1 - l1.1;
+        let den = l1.0 - l2.0;
+        let q = num.div_euclid(den) + if num.rem_euclid(den) != 0 {1} else {0};
+        q.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_convex_hull_trick() {
+        let mut rng = Rng::new();
+        for _ in 0..50 {
+            let mut lines = (0..30).map(|_| (rng.int(-20, 21), rng.int(-1000, 1001))).collect::<Vec<_>>();
+            lines.sort_by(|x, y| y.cmp(x));
+            let mut min = ConvexHullTrick::new();
+            let mut max = ConvexHullTrick::new_max();
+            let mut xs = (0..50).map(|_| rng.int(-100, 101)).collect::<Vec<i64>>();
+            xs.sort();
+            let mut qi = 0;
+            for (i, &(a, b)) in lines.iter().enumerate() {
+                min.add_line(a, b);
+                let (ra, rb) = lines[lines.len() - 1 - i];
+                max.add_line(ra, rb);
+                let added = &lines[..=i];
+                let added_rev = &lines[lines.len() - 1 - i..];
+                let x = rng.int(-100, 101);
+                let lo = added.iter().map(|&(a, b)| (a * x + b) as i128).min().unwrap();
+                let hi = added_rev.iter().map(|&(a, b)| (a * x + b) as i128).max().unwrap();
+                assert_eq!(min.query(x), lo);
+                assert_eq!(max.query(x), hi);
+                if i % 2 == 1 {
+                    let x = xs[qi];
+                    qi += 1;
+                    let lo = added.iter().map(|&(a, b)| (a * x + b) as i128).min().unwrap();
+                    assert_eq!(min.query_monotone(x), lo);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_hull_trick_overflow() {
+        let mut cht = ConvexHullTrick::new();
+        cht.add_line(1_000_000_000, i64::MAX / 2);
+        cht.add_line(0, 0);
+        cht.add_line(-1_000_000_000, i64::MAX / 2);
+        cht.add_line(-1_000_000_000, -1_000_000_000_000_000_000);
+        assert_eq!(cht.query(0), -1_000_000_000_000_000_000);
+        assert_eq!(cht.query(-2_000_000_000), 0);
+        assert_eq!(cht.query(1_000_000_000), -2_000_000_000_000_000_000);
+        // below i64::MIN
+        assert_eq!(cht.query(-10_000_000_000), -10_000_000_000_000_000_000 + (i64::MAX / 2) as i128);
+        assert_eq!(cht.len(), 3);
+    }
+
+    #[test]
+    fn test_convex_hull_trick_extreme() {
+        let lines = [(i64::MAX, i64::MIN), (i64::MIN + 1, 0), (i64::MIN, i64::MAX)];
+        let mut min = ConvexHullTrick::new();
+        let mut max = ConvexHullTrick::new_max();
+        for (i, &(a, b)) in lines.iter().enumerate() {
+            min.add_line(a, b);
+            let (ra, rb) = lines[lines.len() - 1 - i];
+            max.add_line(ra, rb);
+        }
+        for x in [i64::MIN, i64::MIN + 1, -2, -1, 0, 1, 2, i64::MAX - 1, i64::MAX] {
+            let vals = lines.iter().map(|&(a, b)| a as i128 * x as i128 + b as i128);
+            assert_eq!(min.query(x), vals.clone().min().unwrap());
+            assert_eq!(max.query(x), vals.max().unwrap());
+        }
+    }
+}
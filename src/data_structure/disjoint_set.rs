@@ -8,7 +8,7 @@ impl DisjointSet {
     pub fn new(n: usize) -> Self{
         Self {
             _n: n,
-            data: (0..n).map(|i| i).collect::<Vec<usize>>(),
+            data: (0..n).collect::<Vec<usize>>(),
             size: vec![1; n],
         }
     }
@@ -24,11 +24,12 @@ impl DisjointSet {
         }
     }
 
-    pub fn unite(&mut self, x: usize, y: usize) {
+    // returns the new root if two different trees were merged
+    pub fn unite(&mut self, x: usize, y: usize) -> Option<usize> {
         let mut px = self.find(x);
         let mut py = self.find(y);
         if px == py {
-            return;
+            return None;
         }
         if self.size[py] < self.size[px] {
             std::mem::swap(&mut px, &mut py);
@@ -36,6 +37,7 @@ impl DisjointSet {
         self.data[px] = py;
         self.size[py] += self.size[px];
         self._n -= 1;
+        Some(py)
     }
 
     pub fn same(&mut self, x: usize, y: usize) -> bool {
@@ -53,6 +55,100 @@ impl DisjointSet {
         self.size[px]
     }
 
+    pub fn roots(&mut self) -> Vec<usize> {
+        (0..self.data.len()).filter(|&i| self.find(i) == i).collect()
+    }
+
+    pub fn members(&mut self, x: usize) -> Vec<usize> {
+        let px = self.find(x);
+        (0..self.data.len()).filter(|&i| self.find(i) == px).collect()
+    }
+
+    // groups are ordered by their smallest member
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.data.len();
+        let mut id = vec![usize::MAX; n];
+        let mut res: Vec<Vec<usize>> = Vec::with_capacity(self._n);
+        for i in 0..n {
+            let r = self.find(i);
+            if id[r] == usize::MAX {
+                id[r] = res.len();
+                res.push(Vec::new());
+            }
+            res[id[r]].push(i);
+        }
+        res
+    }
+}
+
+// merge(data of the new root, data of the merged root)
+pub struct DisjointSetWith<M, F> {
+    uf: DisjointSet,
+    value: Vec<Option<M>>,
+    merge: F,
+}
+
+impl<M, F> DisjointSetWith<M, F>
+where
+    F: Fn(M, M) -> M,
+{
+    pub fn new(init: Vec<M>, merge: F) -> Self {
+        Self {
+            uf: DisjointSet::new(init.len()),
+            value: init.into_iter().map(Some).collect(),
+            merge,
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        self.uf.find(x)
+    }
+
+    pub fn unite(&mut self, x: usize, y: usize) -> Option<usize> {
+        let px = self.uf.find(x);
+        let py = self.uf.find(y);
+        let r = self.uf.unite(px, py)?;
+        let c = if r == px {py} else {px};
+        let vr = self.value[r].take().unwrap();
+        let vc = self.value[c].take().unwrap();
+        self.value[r] = Some((self.merge)(vr, vc));
+        Some(r)
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.uf.same(x, y)
+    }
+
+    pub fn num_trees(&self) -> usize {
+        self.uf.num_trees()
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        self.uf.size(x)
+    }
+
+    // data of the component containing x
+    pub fn get(&mut self, x: usize) -> &M {
+        let px = self.uf.find(x);
+        self.value[px].as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self, x: usize) -> &mut M {
+        let px = self.uf.find(x);
+        self.value[px].as_mut().unwrap()
+    }
+
+    pub fn roots(&mut self) -> Vec<usize> {
+        self.uf.roots()
+    }
+
+    pub fn members(&mut self, x: usize) -> Vec<usize> {
+        self.uf.members(x)
+    }
+
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        self.uf.groups()
+    }
 }
 
 #[cfg(test)]
@@ -71,6 +167,42 @@ mod tests {
         assert_eq!(uf.num_trees(), 2);
         assert_eq!(uf.size(1), 3);
         assert_eq!(uf.size(3), 1);
+        assert_eq!(uf.unite(3, 0), Some(uf.find(1)));
+        assert_eq!(uf.unite(2, 3), None);
+    }
+
+    #[test]
+    fn dsu_groups_test() {
+        let mut uf = DisjointSet::new(6);
+        uf.unite(4, 1);
+        uf.unite(3, 5);
+        uf.unite(5, 4);
+        assert_eq!(uf.groups(), vec![vec![0], vec![1, 3, 4, 5], vec![2]]);
+        assert_eq!(uf.members(3), vec![1, 3, 4, 5]);
+        assert_eq!(uf.members(2), vec![2]);
+        let roots = uf.roots();
+        assert_eq!(roots.len(), uf.num_trees());
+        assert!(roots.iter().all(|&r| uf.find(r) == r));
+
+        let mut uf = DisjointSetWith::new(vec![5, 3, 8, 1], |a: i32, b: i32| a.min(b));
+        uf.unite(0, 2);
+        assert_eq!(*uf.get(2), 5);
+        uf.unite(2, 3);
+        assert_eq!(*uf.get(0), 1);
+        assert_eq!(uf.unite(0, 3), None);
+        *uf.get_mut(1) += 10;
+        assert_eq!(*uf.get(1), 13);
+
+        let mut uf = DisjointSetWith::new((0..4).map(|i| vec![i]).collect(), |mut a: Vec<usize>, mut b| {
+            a.append(&mut b);
+            a
+        });
+        uf.unite(3, 1);
+        uf.unite(0, 1);
+        let mut v = uf.get(0).clone();
+        v.sort();
+        assert_eq!(v, vec![0, 1, 3]);
+        assert_eq!(uf.get(2), &vec![2]);
     }
 
     #[test]
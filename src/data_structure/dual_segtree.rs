@@ -0,0 +1,181 @@
+use crate::data_structure::algebra::Monoid;
+
+// range apply / point get, elements are maps and op(f, g) applies g first, then f
+// only the lazy array is kept, so op need not be commutative
+pub struct DualSegtree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    size: usize,
+    log: usize,
+    lz: Vec<M::S>,
+    m: M,
+}
+
+impl<M> DualSegtree<M>
+where
+    M: Monoid,
+{
+    pub fn with_monoid(n: usize, m: M) -> Self {
+        let e = m.identity();
+        Self::from_monoid(vec![e; n], m)
+    }
+
+    pub fn from_monoid(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let size = n.next_power_of_two();
+        let log = size.trailing_zeros() as usize;
+        let mut lz = vec![m.identity(); 2 * size];
+        lz[size..(size + n)].clone_from_slice(&v);
+        Self {
+            n,
+            size,
+            log,
+            lz,
+            m,
+        }
+    }
+
+    pub fn get(&mut self, mut p: usize) -> M::S {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.lz[p].clone()
+    }
+
+    pub fn set(&mut self, mut p: usize, f: M::S) {
+        assert!(p < self.n);
+        p += self.size;
+        for i in (1..=self.log).rev() {
+            self.push(p >> i);
+        }
+        self.lz[p] = f;
+    }
+
+    pub fn apply(&mut self, p: usize, f: M::S) {
+        self.apply_range(p, p + 1, f);
+    }
+
+    pub fn apply_range(&mut self, left: usize, right: usize, f: M::S) {
+        assert!(left <= right);
+        assert!(right <= self.n);
+        if left == right {
+            return;
+        }
+        let mut l = left + self.size;
+        let mut r = right + self.size;
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+        while l < r {
+            if l & 1 != 0 {
+                self.all_apply(l, &f);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                self.all_apply(r, &f);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+    }
+
+    // pushes every lazy value down to the leaves
+    pub fn to_vec(&mut self) -> Vec<M::S> {
+        for k in 1..self.size {
+            self.push(k);
+        }
+        self.lz[self.size..(self.size + self.n)].to_vec()
+    }
+
+    fn all_apply(&mut self, k: usize, f: &M::S) {
+        self.lz[k] = self.m.op(f, &self.lz[k]);
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lz[k], self.m.identity());
+        self.all_apply(2 * k, &f);
+        self.all_apply(2 * k + 1, &f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    // x -> a * x + b
+    struct Affine;
+
+    impl Monoid for Affine {
+        type S = (i64, i64);
+
+        fn identity(&self) -> (i64, i64) {
+            (1, 0)
+        }
+
+        fn op(&self, f: &(i64, i64), g: &(i64, i64)) -> (i64, i64) {
+            const MOD: i64 = 998244353;
+            (f.0 * g.0 % MOD, (f.0 * g.1 + f.1) % MOD)
+        }
+    }
+
+    #[test]
+    fn test_dual_segtree() {
+        const MOD: i64 = 998244353;
+        let mut rng = Rng::new();
+        for n in 0..20 {
+            let mut a = vec![(1i64, 0i64); n];
+            let mut st = DualSegtree::with_monoid(n, Affine);
+            for _ in 0..200 {
+                let (l, r) = rng.range(n);
+                if rng.next_usize().is_multiple_of(2) {
+                    let f = ((rng.next_usize() % 10) as i64, (rng.next_usize() % 10) as i64);
+                    st.apply_range(l, r, f);
+                    a[l..r].iter_mut().for_each(|g| *g = (f.0 * g.0 % MOD, (f.0 * g.1 + f.1) % MOD));
+                }
+                else if l < n {
+                    assert_eq!(st.get(l), a[l]);
+                }
+            }
+            assert_eq!(st.to_vec(), a);
+        }
+    }
+
+    #[test]
+    fn test_dual_segtree_assign() {
+        // range assign with timestamps: (time, value), the later one wins
+        struct Assign;
+
+        impl Monoid for Assign {
+            type S = (usize, i64);
+
+            fn identity(&self) -> (usize, i64) {
+                (0, 0)
+            }
+
+            fn op(&self, f: &(usize, i64), g: &(usize, i64)) -> (usize, i64) {
+                *f.max(g)
+            }
+        }
+
+        let mut st = DualSegtree::with_monoid(6, Assign);
+        st.apply_range(0, 4, (1, 5));
+        st.apply_range(2, 6, (2, -1));
+        st.apply(3, (3, 8));
+        assert_eq!(st.get(1).1, 5);
+        assert_eq!(st.get(3).1, 8);
+        st.set(0, (4, 9));
+        let v = st.to_vec().into_iter().map(|x| x.1).collect::<Vec<_>>();
+        assert_eq!(v, vec![9, 5, -1, 8, -1, -1]);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::data_structure::algebra::MapMonoid;
+
+struct Node<S, F> {
+    val: S,
+    lz: F,
+    ch: [usize; 2],
+}
+
+// lazy segment tree over [lo, hi) of i64, nodes are allocated on demand in one Vec
+// every element starts as x0, a MapMonoid with F = () makes it a non-lazy tree
+// [lo, hi) is padded up to 2^log cells with identity, so nothing is folded beyond the real range
+// hi - lo <= 2^62, O(log(hi - lo)) per operation
+pub struct DynamicSegtree<M>
+where
+    M: MapMonoid,
+{
+    lo: i64,
+    hi: i64,
+    log: usize,
+    // pw[d] = x0 * x0 * ... (2^d times), only for 2^d <= hi - lo
+    pw: Vec<M::S>,
+    // edge[d]: the initial value of the node of 2^d cells that straddles hi
+    edge: Vec<M::S>,
+    nodes: Vec<Node<M::S, M::F>>,
+    m: M,
+}
+
+impl<M> DynamicSegtree<M>
+where
+    M: MapMonoid,
+{
+    pub fn new(lo: i64, hi: i64, x0: M::S, m: M) -> Self {
+        assert!(lo < hi);
+        let len = hi.checked_sub(lo).expect("range too large") as u64;
+        assert!(len <= 1 << 62);
+        let log = len.next_power_of_two().trailing_zeros() as usize;
+        let mut pw = vec![x0];
+        while 2 << (pw.len() - 1) <= len {
+            let d = pw.len() - 1;
+            pw.push(m.op(&pw[d], &pw[d]));
+        }
+        // the straddling node of 2^(d + 1) cells holds x0 (len mod 2^(d + 1)) times
+        let mut edge = vec![m.identity()];
+        for d in 0..log {
+            let next = if len >> d & 1 == 1 {m.op(&pw[d], &edge[d])} else {edge[d].clone()};
+            edge.push(next);
+        }
+        let mut ret = Self {
+            lo,
+            hi,
+            log,
+            pw,
+            edge,
+            nodes: Vec::new(),
+            m,
+        };
+        ret.new_node(log, 0);
+        ret
+    }
+
+    pub fn set(&mut self, p: i64, x: M::S) {
+        assert!(self.lo <= p && p < self.hi);
+        self.set_rec(0, self.log, 0, p - self.lo, x);
+    }
+
+    pub fn get(&mut self, p: i64) -> M::S {
+        self.prod(p, p + 1)
+    }
+
+    pub fn prod(&mut self, left: i64, right: i64) -> M::S {
+        assert!(self.lo <= left && left <= right && right <= self.hi);
+        if left == right {
+            return self.m.identity();
+        }
+        self.prod_rec(0, self.log, 0, left - self.lo, right - self.lo)
+    }
+
+    pub fn all_prod(&mut self) -> M::S {
+        self.prod(self.lo, self.hi)
+    }
+
+    pub fn apply(&mut self, p: i64, f: M::F) {
+        self.apply_range(p, p + 1, f);
+    }
+
+    pub fn apply_range(&mut self, left: i64, right: i64, f: M::F) {
+        assert!(self.lo <= left && left <= right && right <= self.hi);
+        if left == right {
+            return;
+        }
+        self.apply_rec(0, self.log, 0, left - self.lo, right - self.lo, &f);
+    }
+
+    // the largest r with pred(prod(left, r)), pred(e) must hold
+    pub fn max_right<P>(&mut self, left: i64, pred: P) -> i64
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(self.lo <= left && left <= self.hi);
+        assert!(pred(&self.m.identity()));
+        if left == self.hi {
+            return self.hi;
+        }
+        let mut sm = self.m.identity();
+        match self.max_right_rec(0, self.log, 0, left - self.lo, &pred, &mut sm) {
+            Some(r) => (r + self.lo).min(self.hi),
+            None => self.hi,
+        }
+    }
+
+    // the node of 2^d cells starting at nl
+    fn new_node(&mut self, d: usize, nl: i64) -> usize {
+        let len = self.hi - self.lo;
+        let val = if nl >= len {
+            self.m.identity()
+        }
+        else if len - nl >= 1 << d {
+            self.pw[d].clone()
+        }
+        else {
+            self.edge[d].clone()
+        };
+        self.nodes.push(Node {
+            val,
+            lz: self.m.identity_map(),
+            ch: [0, 0],
+        });
+        self.nodes.len() - 1
+    }
+
+    // k covers [nl, nl + 2^d)
+    fn push(&mut self, k: usize, d: usize, nl: i64) {
+        for i in 0..2 {
+            if self.nodes[k].ch[i] == 0 {
+                let c = self.new_node(d - 1, nl + ((i as i64) << (d - 1)));
+                self.nodes[k].ch[i] = c;
+            }
+        }
+        let f = std::mem::replace(&mut self.nodes[k].lz, self.m.identity_map());
+        for i in 0..2 {
+            let c = self.nodes[k].ch[i];
+            self.all_apply(c, &f);
+        }
+    }
+
+    fn all_apply(&mut self, k: usize, f: &M::F) {
+        let node = &self.nodes[k];
+        let val = self.m.mapping(f, &node.val);
+        let lz = self.m.composition(f, &node.lz);
+        self.nodes[k].val = val;
+        self.nodes[k].lz = lz;
+    }
+
+    fn update(&mut self, k: usize) {
+        let [a, b] = self.nodes[k].ch;
+        self.nodes[k].val = self.m.op(&self.nodes[a].val, &self.nodes[b].val);
+    }
+
+    fn set_rec(&mut self, k: usize, d: usize, nl: i64, p: i64, x: M::S) {
+        if d == 0 {
+            self.nodes[k].val = x;
+            return;
+        }
+        self.push(k, d, nl);
+        let mid = nl + (1i64 << (d - 1));
+        if p < mid {
+            self.set_rec(self.nodes[k].ch[0], d - 1, nl, p, x);
+        }
+        else {
+            self.set_rec(self.nodes[k].ch[1], d - 1, mid, p, x);
+        }
+        self.update(k);
+    }
+
+    // k covers [nl, nl + 2^d)
+    fn prod_rec(&mut self, k: usize, d: usize, nl: i64, l: i64, r: i64) -> M::S {
+        let nr = nl + (1i64 << d);
+        if r <= nl || nr <= l {
+            return self.m.identity();
+        }
+        if l <= nl && nr <= r {
+            return self.nodes[k].val.clone();
+        }
+        self.push(k, d, nl);
+        let [a, b] = self.nodes[k].ch;
+        let mid = nl + (1i64 << (d - 1));
+        let x = self.prod_rec(a, d - 1, nl, l, r);
+        let y = self.prod_rec(b, d - 1, mid, l, r);
+        self.m.op(&x, &y)
+    }
+
+    fn apply_rec(&mut self, k: usize, d: usize, nl: i64, l: i64, r: i64, f: &M::F) {
+        let nr = nl + (1i64 << d);
+        if r <= nl || nr <= l {
+            return;
+        }
+        if l <= nl && nr <= r {
+            self.all_apply(k, f);
+            return;
+        }
+        self.push(k, d, nl);
+        let [a, b] = self.nodes[k].ch;
+        let mid = nl + (1i64 << (d - 1));
+        self.apply_rec(a, d - 1, nl, l, r, f);
+        self.apply_rec(b, d - 1, mid, l, r, f);
+        self.update(k);
+    }
+
+    // the first position in [l, ..) where pred fails, if any
+    fn max_right_rec<P>(&mut self, k: usize, d: usize, nl: i64, l: i64, pred: &P, sm: &mut M::S) -> Option<i64>
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        let nr = nl + (1i64 << d);
+        if nr <= l {
+            return None;
+        }
+        if l <= nl {
+            let res = self.m.op(sm, &self.nodes[k].val);
+            if pred(&res) {
+                *sm = res;
+                return None;
+            }
+            if d == 0 {
+                return Some(nl);
+            }
+        }
+        self.push(k, d, nl);
+        let [a, b] = self.nodes[k].ch;
+        let mid = nl + (1i64 << (d - 1));
+        self.max_right_rec(a, d - 1, nl, l, pred, sm)
+            .or_else(|| self.max_right_rec(b, d - 1, mid, l, pred, sm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+    use crate::data_structure::algebra::{RangeAddMin, RangeAddSum};
+
+    #[test]
+    fn test_dynamic_segtree() {
+        let mut rng = Rng::new();
+        let (lo, hi) = (-17, 23);
+        let n = (hi - lo) as usize;
+        let mut a = vec![3i64; n];
+        let mut sum = DynamicSegtree::new(lo, hi, (3, 1), RangeAddSum);
+        let mut min = DynamicSegtree::new(lo, hi, 3, RangeAddMin);
+        for _ in 0..2000 {
+            let (l, r) = rng.range(n);
+            let (cl, cr) = (l as i64 + lo, r as i64 + lo);
+            let x = (rng.next_usize() % 11) as i64;
+            match rng.next_usize() % 4 {
+                0 => {
+                    sum.apply_range(cl, cr, x);
+                    min.apply_range(cl, cr, x);
+                    a[l..r].iter_mut().for_each(|v| *v += x);
+                }
+                1 if l < n => {
+                    sum.set(cl, (x, 1));
+                    min.set(cl, x);
+                    a[l] = x;
+                }
+                2 => {
+                    let k = (rng.next_usize() % 100) as i64;
+                    let mut r = l;
+                    while r < n && a[l..=r].iter().sum::<i64>() <= k {
+                        r += 1;
+                    }
+                    assert_eq!(sum.max_right(cl, |s| s.0 <= k), r as i64 + lo);
+                }
+                _ => {
+                    assert_eq!(sum.prod(cl, cr).0, a[l..r].iter().sum::<i64>());
+                    assert_eq!(min.prod(cl, cr), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_segtree_huge() {
+        let (lo, hi) = (-1_000_000_000_000_000_000, 1_000_000_000_000_000_001);
+        let mut st = DynamicSegtree::new(lo, hi, (0, 1), RangeAddSum);
+        st.apply_range(-5, 5, 2);
+        st.apply_range(0, hi, 1);
+        st.set(lo, (7, 1));
+        assert_eq!(st.prod(-5, 5), (25, 10));
+        assert_eq!(st.get(lo), (7, 1));
+        assert_eq!(st.get(hi - 1), (1, 1));
+        assert_eq!(st.prod(10, 20), (10, 10));
+        assert_eq!(st.all_prod(), (7 + 20 + 1_000_000_000_000_000_001, 2_000_000_000_000_000_001));
+        assert_eq!(st.max_right(lo, |s| s.0 <= 7), -5);
+        assert_eq!(st.max_right(-5, |s| s.0 <= 12), 0);
+        assert!(st.nodes.len() < 1000);
+    }
+
+    #[test]
+    fn test_dynamic_segtree_no_padding_overflow() {
+        // 2^log is about twice hi - lo, so folding x0 over the padding would overflow
+        let (lo, hi) = (-1_500_000_000_000_000_000, 1_500_000_000_000_000_000);
+        let mut st = DynamicSegtree::new(lo, hi, (2, 1), RangeAddSum);
+        assert_eq!(st.all_prod(), (6_000_000_000_000_000_000, 3_000_000_000_000_000_000));
+        assert_eq!(st.prod(hi - 10, hi), (20, 10));
+        st.apply_range(hi - 3, hi, 1);
+        assert_eq!(st.prod(hi - 10, hi), (23, 10));
+        assert_eq!(st.max_right(hi - 10, |s| s.0 <= 100), hi);
+
+        // exactly 2^62 cells
+        let (lo, hi) = (-(1i64 << 61), 1i64 << 61);
+        let mut st = DynamicSegtree::new(lo, hi, (1, 1), RangeAddSum);
+        assert_eq!(st.all_prod(), (1 << 62, 1 << 62));
+        st.set(hi - 1, (-(1 << 62), 1));
+        assert_eq!(st.all_prod(), (-1, 1 << 62));
+        assert_eq!(st.prod(0, hi), ((1 << 61) - 1 - (1 << 62), 1 << 61));
+    }
+}
@@ -1,5 +1,6 @@
-use std::ops::{Add, Sub, AddAssign};
+use std::ops::{Add, Sub, AddAssign, Bound, RangeBounds};
 
+// 0-indexed, ranges are half-open
 pub struct FenwickTree<T>
 where
     T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
@@ -15,21 +16,51 @@ where
 {
     pub fn new(n: usize, e: T) -> Self{
         Self {
-            n: n,
+            n,
             data: vec![e; n+1],
-            e: e,
+            e,
         }
     }
 
-    pub fn add(&mut self, mut id: usize, x: T) {
+    // O(n)
+    pub fn from_slice(v: &[T], e: T) -> Self {
+        let n = v.len();
+        let mut data = vec![e; n+1];
+        data[1..].copy_from_slice(v);
+        for i in 1..=n {
+            let j = i + (i & i.wrapping_neg());
+            if j <= n {
+                let x = data[i];
+                data[j] += x;
+            }
+        }
+        Self {
+            n,
+            data,
+            e,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn add(&mut self, i: usize, x: T) {
+        assert!(i < self.n);
+        let mut id = i + 1;
         while id <= self.n {
             self.data[id] += x;
-            id += id & !id + 1;
+            id += id & id.wrapping_neg();
         }
     }
 
-    // [0, r]
-    pub fn sum(&self, mut r: usize) -> T {
+    // [0, r)
+    pub fn prefix(&self, mut r: usize) -> T {
+        assert!(r <= self.n);
         let mut res: T = self.e;
         while r != 0 {
             res += self.data[r];
@@ -38,25 +69,331 @@ where
         res
     }
 
-    // [l, r]
-    pub fn sum2(&self, l: usize, r: usize) -> T {
-        self.sum(r) - self.sum(l-1)
+    pub fn sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.n,
+        };
+        assert!(l <= r && r <= self.n);
+        self.prefix(r) - self.prefix(l)
+    }
+
+    pub fn get(&self, i: usize) -> T {
+        self.sum(i..=i)
+    }
+
+    pub fn set(&mut self, i: usize, x: T) {
+        let cur = self.get(i);
+        self.add(i, x - cur);
+    }
+
+    // the largest r with pred(prefix(r)), pred must be monotone and pred(e) must hold
+    pub fn max_right<P>(&self, pred: P) -> usize
+    where
+        P: Fn(T) -> bool,
+    {
+        assert!(pred(self.e));
+        let mut pos = 0;
+        let mut acc = self.e;
+        let mut k = (self.n + 1).next_power_of_two() >> 1;
+        while k > 0 {
+            if pos + k <= self.n && pred(acc + self.data[pos + k]) {
+                pos += k;
+                acc += self.data[pos];
+            }
+            k >>= 1;
+        }
+        pos
+    }
+}
+
+impl<T> FenwickTree<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy + PartialOrd,
+{
+    // the smallest i with prefix(i + 1) >= w (n if none), all elements must be non-negative
+    pub fn lower_bound(&self, w: T) -> usize {
+        if w <= self.e {
+            return 0;
+        }
+        self.max_right(|s| s < w)
+    }
+}
+
+// x * k using only additions
+fn mul<T>(mut x: T, mut k: usize, e: T) -> T
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    let mut res = e;
+    while k > 0 {
+        if k & 1 == 1 {
+            res += x;
+        }
+        x += x;
+        k >>= 1;
+    }
+    res
+}
+
+// range add / range sum, 0-indexed and half-open
+pub struct RangeFenwickTree<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    n: usize,
+    b0: FenwickTree<T>,
+    b1: FenwickTree<T>,
+    e: T,
+}
+
+impl<T> RangeFenwickTree<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    pub fn new(n: usize, e: T) -> Self {
+        Self {
+            n,
+            b0: FenwickTree::new(n + 1, e),
+            b1: FenwickTree::new(n + 1, e),
+            e,
+        }
+    }
+
+    // [l, r) += x
+    pub fn add(&mut self, l: usize, r: usize, x: T) {
+        assert!(l <= r && r <= self.n);
+        let e = self.e;
+        self.b0.add(l, e - mul(x, l, e));
+        self.b1.add(l, x);
+        self.b0.add(r, mul(x, r, e));
+        self.b1.add(r, e - x);
+    }
+
+    // [0, r)
+    pub fn prefix(&self, r: usize) -> T {
+        assert!(r <= self.n);
+        self.b0.prefix(r) + mul(self.b1.prefix(r), r, self.e)
+    }
+
+    // [l, r)
+    pub fn sum(&self, l: usize, r: usize) -> T {
+        assert!(l <= r);
+        self.prefix(r) - self.prefix(l)
+    }
+}
+
+// range add / range sum on a grid, 0-indexed and half-open
+// point add and point get are the 1x1 cases
+pub struct FenwickTree2D<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    h: usize,
+    w: usize,
+    data: Vec<[T; 4]>,
+    e: T,
+}
+
+impl<T> FenwickTree2D<T>
+where
+    T: AddAssign + Add<Output = T> + Sub<Output = T> + Copy,
+{
+    pub fn new(h: usize, w: usize, e: T) -> Self {
+        Self {
+            h,
+            w,
+            data: vec![[e; 4]; (h + 2) * (w + 2)],
+            e,
+        }
+    }
+
+    // [x1, x2) * [y1, y2) += v
+    pub fn add_range(&mut self, x1: usize, x2: usize, y1: usize, y2: usize, v: T) {
+        assert!(x1 <= x2 && x2 <= self.h);
+        assert!(y1 <= y2 && y2 <= self.w);
+        if x1 == x2 || y1 == y2 {
+            return;
+        }
+        let nv = self.e - v;
+        self.add_corner(x1, y1, v);
+        self.add_corner(x1, y2, nv);
+        self.add_corner(x2, y1, nv);
+        self.add_corner(x2, y2, v);
+    }
+
+    pub fn add(&mut self, x: usize, y: usize, v: T) {
+        self.add_range(x, x + 1, y, y + 1, v);
+    }
+
+    // [x1, x2) * [y1, y2)
+    pub fn sum(&self, x1: usize, x2: usize, y1: usize, y2: usize) -> T {
+        assert!(x1 <= x2 && x2 <= self.h);
+        assert!(y1 <= y2 && y2 <= self.w);
+        self.prefix(x2, y2) - self.prefix(x1, y2) - self.prefix(x2, y1) + self.prefix(x1, y1)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> T {
+        self.sum(x, x + 1, y, y + 1)
+    }
+
+    // every cell (i, j) with a <= i, b <= j gets v
+    fn add_corner(&mut self, a: usize, b: usize, v: T) {
+        let e = self.e;
+        let c = [v, e - mul(v, b, e), e - mul(v, a, e), mul(v, a * b, e)];
+        let mut i = a + 1;
+        while i <= self.h + 1 {
+            let mut j = b + 1;
+            while j <= self.w + 1 {
+                let d = &mut self.data[i * (self.w + 2) + j];
+                for k in 0..4 {
+                    d[k] += c[k];
+                }
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    // [0, x) * [0, y)
+    fn prefix(&self, x: usize, y: usize) -> T {
+        let mut s = [self.e; 4];
+        let mut i = x;
+        while i != 0 {
+            let mut j = y;
+            while j != 0 {
+                let d = &self.data[i * (self.w + 2) + j];
+                for k in 0..4 {
+                    s[k] += d[k];
+                }
+                j &= j - 1;
+            }
+            i &= i - 1;
+        }
+        let e = self.e;
+        mul(s[0], x * y, e) + mul(s[1], x, e) + mul(s[2], y, e) + s[3]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::rng::Rng;
+    use crate::math::modint::ModInt;
 
     #[test]
     fn fw_test() {
         let mut bt = FenwickTree::new(3, 0);
-        bt.add(1, 1);
-        bt.add(2, 2);
-        bt.add(3, 3);
-        assert_eq!(bt.sum(2), 3);
-        assert_eq!(bt.sum2(1, 2), 3);
-        assert_eq!(bt.sum(3), 6);
-        assert_eq!(bt.sum2(2, 2), 2);
+        bt.add(0, 1);
+        bt.add(1, 2);
+        bt.add(2, 3);
+        assert_eq!(bt.prefix(2), 3);
+        assert_eq!(bt.sum(0..2), 3);
+        assert_eq!(bt.sum(..), 6);
+        assert_eq!(bt.sum(1..=1), 2);
+        assert_eq!(bt.sum(1..), 5);
+        assert_eq!(bt.sum(..=0), 1);
+        assert_eq!(bt.sum(2..2), 0);
+        bt.set(1, -4);
+        assert_eq!(bt.get(1), -4);
+        assert_eq!(bt.sum(..), 0);
+
+        let a = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let bt = FenwickTree::from_slice(&a, 0);
+        for l in 0..=a.len() {
+            for r in l..=a.len() {
+                assert_eq!(bt.sum(l..r), a[l..r].iter().sum::<i32>());
+            }
+        }
+    }
+
+    #[test]
+    fn fw_lower_bound_test() {
+        let mut rng = Rng::new();
+        // multiset over [0, 20)
+        let n = 20;
+        let mut bt = FenwickTree::new(n, 0usize);
+        let mut a = Vec::new();
+        for _ in 0..300 {
+            let x = rng.next_usize() % n;
+            bt.add(x, 1);
+            a.push(x);
+            a.sort();
+            let k = rng.next_usize() % a.len();
+            assert_eq!(bt.lower_bound(k + 1), a[k]);
+            assert_eq!(bt.lower_bound(a.len() + 1), n);
+            let lim = rng.next_usize() % (a.len() + 1);
+            let r = bt.max_right(|s| s <= lim);
+            assert!(bt.prefix(r) <= lim);
+            assert!(r == n || bt.prefix(r + 1) > lim);
+        }
+    }
+
+    #[test]
+    fn range_fw_test() {
+        let mut rng = Rng::new();
+        let n = 30;
+        let mut bt = RangeFenwickTree::new(n, 0i64);
+        let mut a = vec![0i64; n];
+        for _ in 0..1000 {
+            let (l, r) = rng.range(n);
+            if rng.next_usize().is_multiple_of(2) {
+                let x = (rng.next_usize() % 200) as i64 - 100;
+                bt.add(l, r, x);
+                a[l..r].iter_mut().for_each(|v| *v += x);
+            }
+            else {
+                assert_eq!(bt.sum(l, r), a[l..r].iter().sum::<i64>());
+            }
+        }
+
+        const MOD: usize = 998244353;
+        let mut bt = RangeFenwickTree::new(4, ModInt::<MOD>::new(0));
+        bt.add(1, 4, ModInt::new(MOD - 1));
+        bt.add(0, 2, ModInt::new(3));
+        assert_eq!(bt.sum(0, 4), ModInt::new(3));
+        assert_eq!(bt.sum(2, 4), ModInt::new(MOD - 2));
+    }
+
+    #[test]
+    fn fw_2d_test() {
+        let mut rng = Rng::new();
+        let (h, w) = (7, 9);
+        let mut bt = FenwickTree2D::new(h, w, 0i64);
+        let mut a = vec![vec![0i64; w]; h];
+        for _ in 0..1000 {
+            let (mut x1, mut x2) = (rng.next_usize() % (h + 1), rng.next_usize() % (h + 1));
+            let (mut y1, mut y2) = (rng.next_usize() % (w + 1), rng.next_usize() % (w + 1));
+            if x1 > x2 {
+                std::mem::swap(&mut x1, &mut x2);
+            }
+            if y1 > y2 {
+                std::mem::swap(&mut y1, &mut y2);
+            }
+            let v = (rng.next_usize() % 200) as i64 - 100;
+            match rng.next_usize() % 4 {
+                0 => {
+                    bt.add_range(x1, x2, y1, y2, v);
+                    (x1..x2).for_each(|i| (y1..y2).for_each(|j| a[i][j] += v));
+                }
+                1 if x1 < h && y1 < w => {
+                    bt.add(x1, y1, v);
+                    a[x1][y1] += v;
+                }
+                2 if x1 < h && y1 < w => {
+                    assert_eq!(bt.get(x1, y1), a[x1][y1]);
+                }
+                _ => {
+                    let s = (x1..x2).map(|i| a[i][y1..y2].iter().sum::<i64>()).sum::<i64>();
+                    assert_eq!(bt.sum(x1, x2, y1, y2), s);
+                }
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,421 @@
+use crate::data_structure::algebra::{ClosureMapMonoid, MapMonoid};
+
+struct Node<S, F> {
+    val: S,
+    // products of the subtree from left to right and from right to left
+    sum: S,
+    rsum: S,
+    lz: F,
+    rev: bool,
+    size: usize,
+    pri: u64,
+    ch: [usize; 2],
+}
+
+// randomized treap keyed by position, all nodes of all sequences live in one Vec
+// a sequence is the index of its root node and 0 is the empty sequence
+// operations that restructure a sequence return its new root, erased nodes are not reused
+pub struct ImplicitTreap<M>
+where
+    M: MapMonoid,
+{
+    nodes: Vec<Node<M::S, M::F>>,
+    seed: u64,
+    m: M,
+}
+
+impl<S, T, F, G, H> ImplicitTreap<ClosureMapMonoid<S, T, F, G, H>>
+where
+    S: Copy,
+    T: Copy,
+    F: Fn(S, S) -> S,
+    G: Fn(T, S) -> S,
+    H: Fn(T, T) -> T
+{
+    pub fn new(op: F, e: S, mapping: G, composition: H, id: T) -> Self {
+        Self::with_monoid(ClosureMapMonoid::new(op, e, mapping, composition, id))
+    }
+}
+
+impl<M> ImplicitTreap<M>
+where
+    M: MapMonoid,
+{
+    pub fn with_monoid(m: M) -> Self {
+        let nil = Node {
+            val: m.identity(),
+            sum: m.identity(),
+            rsum: m.identity(),
+            lz: m.identity_map(),
+            rev: false,
+            size: 0,
+            pri: 0,
+            ch: [0, 0],
+        };
+        Self {
+            nodes: vec![nil],
+            seed: 88172645463325252,
+            m,
+        }
+    }
+
+    // new sequence made of v
+    pub fn build(&mut self, v: Vec<M::S>) -> usize {
+        v.into_iter().fold(0, |t, x| {
+            let k = self.new_node(x);
+            self.merge(t, k)
+        })
+    }
+
+    pub fn len(&self, t: usize) -> usize {
+        self.nodes[t].size
+    }
+
+    pub fn is_empty(&self, t: usize) -> bool {
+        t == 0
+    }
+
+    pub fn insert(&mut self, t: usize, i: usize, x: M::S) -> usize {
+        assert!(i <= self.len(t));
+        let (a, b) = self.split(t, i);
+        let k = self.new_node(x);
+        let a = self.merge(a, k);
+        self.merge(a, b)
+    }
+
+    pub fn erase(&mut self, t: usize, i: usize) -> usize {
+        assert!(i < self.len(t));
+        let (a, b) = self.split(t, i);
+        let (_, c) = self.split(b, 1);
+        self.merge(a, c)
+    }
+
+    // ([0, k), [k, len))
+    pub fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        assert!(k <= self.len(t));
+        if t == 0 {
+            return (0, 0);
+        }
+        self.push(t);
+        let [l, r] = self.nodes[t].ch;
+        let ls = self.nodes[l].size;
+        if k <= ls {
+            let (a, b) = self.split(l, k);
+            self.nodes[t].ch[0] = b;
+            self.update(t);
+            (a, t)
+        }
+        else {
+            let (a, b) = self.split(r, k - ls - 1);
+            self.nodes[t].ch[1] = a;
+            self.update(t);
+            (t, b)
+        }
+    }
+
+    // a followed by b
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == 0 || b == 0 {
+            return a + b;
+        }
+        if self.nodes[a].pri > self.nodes[b].pri {
+            self.push(a);
+            let c = self.merge(self.nodes[a].ch[1], b);
+            self.nodes[a].ch[1] = c;
+            self.update(a);
+            a
+        }
+        else {
+            self.push(b);
+            let c = self.merge(a, self.nodes[b].ch[0]);
+            self.nodes[b].ch[0] = c;
+            self.update(b);
+            b
+        }
+    }
+
+    pub fn reverse(&mut self, t: usize, l: usize, r: usize) -> usize {
+        assert!(l <= r && r <= self.len(t));
+        let (a, b) = self.split(t, l);
+        let (b, c) = self.split(b, r - l);
+        self.toggle(b);
+        let b = self.merge(b, c);
+        self.merge(a, b)
+    }
+
+    pub fn get(&mut self, t: usize, i: usize) -> M::S {
+        assert!(i < self.len(t));
+        let (mut k, mut i) = (t, i);
+        loop {
+            self.push(k);
+            let [l, r] = self.nodes[k].ch;
+            let ls = self.nodes[l].size;
+            if i < ls {
+                k = l;
+            }
+            else if i == ls {
+                return self.nodes[k].val.clone();
+            }
+            else {
+                k = r;
+                i -= ls + 1;
+            }
+        }
+    }
+
+    pub fn set(&mut self, t: usize, i: usize, x: M::S) {
+        assert!(i < self.len(t));
+        self.set_rec(t, i, x);
+    }
+
+    pub fn prod(&mut self, t: usize, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.len(t));
+        self.prod_rec(t, l, r)
+    }
+
+    pub fn all_prod(&self, t: usize) -> M::S {
+        self.nodes[t].sum.clone()
+    }
+
+    pub fn apply_range(&mut self, t: usize, l: usize, r: usize, f: M::F) {
+        assert!(l <= r && r <= self.len(t));
+        self.apply_rec(t, l, r, &f);
+    }
+
+    pub fn to_vec(&mut self, t: usize) -> Vec<M::S> {
+        let mut res = Vec::with_capacity(self.len(t));
+        self.collect(t, &mut res);
+        res
+    }
+
+    fn rand(&mut self) -> u64 {
+        self.seed ^= self.seed << 7;
+        self.seed ^= self.seed >> 9;
+        self.seed
+    }
+
+    fn new_node(&mut self, x: M::S) -> usize {
+        let pri = self.rand();
+        self.nodes.push(Node {
+            val: x.clone(),
+            sum: x.clone(),
+            rsum: x,
+            lz: self.m.identity_map(),
+            rev: false,
+            size: 1,
+            pri,
+            ch: [0, 0],
+        });
+        self.nodes.len() - 1
+    }
+
+    fn update(&mut self, k: usize) {
+        let [l, r] = self.nodes[k].ch;
+        let (nl, nr, node) = (&self.nodes[l], &self.nodes[r], &self.nodes[k]);
+        let size = nl.size + nr.size + 1;
+        let sum = self.m.op(&self.m.op(&nl.sum, &node.val), &nr.sum);
+        let rsum = self.m.op(&self.m.op(&nr.rsum, &node.val), &nl.rsum);
+        let node = &mut self.nodes[k];
+        node.size = size;
+        node.sum = sum;
+        node.rsum = rsum;
+    }
+
+    fn all_apply(&mut self, k: usize, f: &M::F) {
+        if k == 0 {
+            return;
+        }
+        let node = &self.nodes[k];
+        let val = self.m.mapping(f, &node.val);
+        let sum = self.m.mapping(f, &node.sum);
+        let rsum = self.m.mapping(f, &node.rsum);
+        let lz = self.m.composition(f, &node.lz);
+        let node = &mut self.nodes[k];
+        node.val = val;
+        node.sum = sum;
+        node.rsum = rsum;
+        node.lz = lz;
+    }
+
+    // reverses the subtree k, its children are reversed lazily
+    fn toggle(&mut self, k: usize) {
+        if k == 0 {
+            return;
+        }
+        let node = &mut self.nodes[k];
+        node.ch.swap(0, 1);
+        std::mem::swap(&mut node.sum, &mut node.rsum);
+        node.rev ^= true;
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.nodes[k].lz, self.m.identity_map());
+        let [l, r] = self.nodes[k].ch;
+        self.all_apply(l, &f);
+        self.all_apply(r, &f);
+        if self.nodes[k].rev {
+            self.toggle(l);
+            self.toggle(r);
+            self.nodes[k].rev = false;
+        }
+    }
+
+    fn set_rec(&mut self, k: usize, i: usize, x: M::S) {
+        self.push(k);
+        let [l, r] = self.nodes[k].ch;
+        let ls = self.nodes[l].size;
+        if i < ls {
+            self.set_rec(l, i, x);
+        }
+        else if i == ls {
+            self.nodes[k].val = x;
+        }
+        else {
+            self.set_rec(r, i - ls - 1, x);
+        }
+        self.update(k);
+    }
+
+    // [l, r) relative to the subtree k
+    fn prod_rec(&mut self, k: usize, l: usize, r: usize) -> M::S {
+        if l >= r {
+            return self.m.identity();
+        }
+        if l == 0 && r == self.nodes[k].size {
+            return self.nodes[k].sum.clone();
+        }
+        self.push(k);
+        let [a, b] = self.nodes[k].ch;
+        let ls = self.nodes[a].size;
+        let mut res = self.prod_rec(a, l, r.min(ls));
+        if l <= ls && ls < r {
+            res = self.m.op(&res, &self.nodes[k].val);
+        }
+        if r > ls + 1 {
+            let y = self.prod_rec(b, l.max(ls + 1) - ls - 1, r - ls - 1);
+            res = self.m.op(&res, &y);
+        }
+        res
+    }
+
+    fn apply_rec(&mut self, k: usize, l: usize, r: usize, f: &M::F) {
+        if l >= r {
+            return;
+        }
+        if l == 0 && r == self.nodes[k].size {
+            self.all_apply(k, f);
+            return;
+        }
+        self.push(k);
+        let [a, b] = self.nodes[k].ch;
+        let ls = self.nodes[a].size;
+        self.apply_rec(a, l, r.min(ls), f);
+        if l <= ls && ls < r {
+            self.nodes[k].val = self.m.mapping(f, &self.nodes[k].val);
+        }
+        if r > ls + 1 {
+            self.apply_rec(b, l.max(ls + 1) - ls - 1, r - ls - 1, f);
+        }
+        self.update(k);
+    }
+
+    fn collect(&mut self, k: usize, res: &mut Vec<M::S>) {
+        if k == 0 {
+            return;
+        }
+        self.push(k);
+        let [l, r] = self.nodes[k].ch;
+        self.collect(l, res);
+        res.push(self.nodes[k].val.clone());
+        self.collect(r, res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+    use crate::data_structure::algebra::{MaxSubarray, RangeAddSum, RangeAssignMaxSubarray};
+
+    #[test]
+    fn test_implicit_treap() {
+        let mut rng = Rng::new();
+        let best = |a: &[i64]| {
+            (0..a.len()).flat_map(|l| (l + 1..=a.len()).map(move |r| a[l..r].iter().sum::<i64>())).max()
+        };
+        let mut a = (0..10).map(|_| (rng.next_usize() % 21) as i64 - 10).collect::<Vec<i64>>();
+        let mut sum = ImplicitTreap::with_monoid(RangeAddSum);
+        let mut mss = ImplicitTreap::with_monoid(RangeAssignMaxSubarray);
+        let mut s = sum.build(a.iter().map(|&x| (x, 1)).collect());
+        let mut t = mss.build(a.iter().map(|&x| MaxSubarray::new(x)).collect());
+        for _ in 0..3000 {
+            let n = a.len();
+            let (l, r) = rng.range(n);
+            let x = (rng.next_usize() % 21) as i64 - 10;
+            match rng.next_usize() % 7 {
+                0 if n < 40 => {
+                    s = sum.insert(s, l, (x, 1));
+                    t = mss.insert(t, l, MaxSubarray::new(x));
+                    a.insert(l, x);
+                }
+                1 if l < n => {
+                    s = sum.erase(s, l);
+                    t = mss.erase(t, l);
+                    a.remove(l);
+                }
+                2 => {
+                    s = sum.reverse(s, l, r);
+                    t = mss.reverse(t, l, r);
+                    a[l..r].reverse();
+                }
+                3 => {
+                    sum.apply_range(s, l, r, x);
+                    a[l..r].iter_mut().for_each(|v| *v += x);
+                    t = mss.build(a.iter().map(|&x| MaxSubarray::new(x)).collect());
+                }
+                4 => {
+                    mss.apply_range(t, l, r, Some(x));
+                    a[l..r].iter_mut().for_each(|v| *v = x);
+                    s = sum.build(a.iter().map(|&x| (x, 1)).collect());
+                }
+                5 if l < n => {
+                    sum.set(s, l, (x, 1));
+                    mss.set(t, l, MaxSubarray::new(x));
+                    a[l] = x;
+                    assert_eq!(sum.get(s, l), (x, 1));
+                }
+                _ => {
+                    assert_eq!(sum.prod(s, l, r), (a[l..r].iter().sum(), (r - l) as i64));
+                    assert_eq!(Some(mss.prod(t, l, r).best).filter(|&b| b != i64::MIN), best(&a[l..r]));
+                }
+            }
+            assert_eq!(sum.len(s), a.len());
+            assert_eq!(sum.all_prod(s).0, a.iter().sum::<i64>());
+            assert_eq!(Some(mss.all_prod(t).best).filter(|&b| b != i64::MIN), best(&a));
+        }
+        assert_eq!(sum.to_vec(s).into_iter().map(|x| x.0).collect::<Vec<_>>(), a);
+    }
+
+    #[test]
+    fn test_implicit_treap_split_merge() {
+        let mut tr = ImplicitTreap::new(
+            |x: i64, y: i64| x + y,
+            0,
+            |f: i64, x: i64| f * x,
+            |f, g| f * g,
+            1,
+        );
+        let a = tr.build((0..10).collect());
+        let (a, b) = tr.split(a, 4);
+        assert_eq!(tr.to_vec(a), vec![0, 1, 2, 3]);
+        assert_eq!(tr.to_vec(b), vec![4, 5, 6, 7, 8, 9]);
+        tr.apply_range(b, 0, 6, -1);
+        let c = tr.merge(b, a);
+        let c = tr.reverse(c, 2, 8);
+        assert_eq!(tr.to_vec(c), vec![-4, -5, 1, 0, -9, -8, -7, -6, 2, 3]);
+        assert_eq!(tr.prod(c, 1, 6), -21);
+        let (d, e) = tr.split(c, 0);
+        assert!(tr.is_empty(d));
+        assert_eq!(tr.len(e), 10);
+    }
+}
@@ -1,18 +1,18 @@
-pub struct LazySegtree<S, T, F, G, H> 
+use crate::data_structure::algebra::{ClosureMapMonoid, MapMonoid};
+
+pub struct LazySegtree<M>
+where
+    M: MapMonoid,
 {
     n: usize,
     size: usize,
     log: usize,
-    data: Vec<S>,
-    lz: Vec<T>,
-    op: F,
-    e: S,
-    mapping: G,
-    composition: H,
-    id: T,
+    data: Vec<M::S>,
+    lz: Vec<M::F>,
+    m: M,
 }
 
-impl<S, T, F, G, H> LazySegtree<S, T, F, G, H> 
+impl<S, T, F, G, H> LazySegtree<ClosureMapMonoid<S, T, F, G, H>>
 where
     S: Copy,
     T: Copy,
@@ -21,40 +21,45 @@ where
     H: Fn(T, T) -> T
 {
     pub fn new(n: usize, op: F, e: S, mapping: G, composition: H, id: T) -> Self {
+        Self::with_monoid(n, ClosureMapMonoid::new(op, e, mapping, composition, id))
+    }
+
+    pub fn from(v: Vec<S>, op: F, e: S, mapping: G, composition: H, id: T) -> Self {
+        Self::from_monoid(v, ClosureMapMonoid::new(op, e, mapping, composition, id))
+    }
+}
+
+impl<M> LazySegtree<M>
+where
+    M: MapMonoid,
+{
+    pub fn with_monoid(n: usize, m: M) -> Self {
         let size = n.next_power_of_two();
         let log = size.trailing_zeros() as usize;
         Self {
             n,
             size,
             log,
-            data: vec![e; size * 2],
-            op,
-            e,
-            lz: vec![id; size],
-            mapping,
-            composition,
-            id,
+            data: vec![m.identity(); size * 2],
+            lz: vec![m.identity_map(); size],
+            m,
         }
     }
 
-    pub fn from(v: Vec<S>, op: F, e: S, mapping: G, composition: H, id: T) -> Self {
+    pub fn from_monoid(v: Vec<M::S>, m: M) -> Self {
         let n = v.len();
         let size = n.next_power_of_two();
         let log = size.trailing_zeros() as usize;
-        let mut data = vec![e; 2 * size];
-        let lz = vec![id; size];
+        let mut data = vec![m.identity(); 2 * size];
+        let lz = vec![m.identity_map(); size];
         data[size..(size + n)].clone_from_slice(&v);
         let mut ret = Self {
             n,
             size,
             log,
             data,
-            op,
-            e,
             lz,
-            mapping,
-            composition,
-            id,
+            m,
         };
         for i in (1..size).rev() {
             ret.update(i);
@@ -62,7 +67,7 @@ where
         ret
     }
 
-    pub fn set(&mut self, mut p: usize, x: S) {
+    pub fn set(&mut self, mut p: usize, x: M::S) {
         assert!(p < self.n);
         p += self.size;
         for i in (1..=self.log).rev() {
@@ -74,20 +79,20 @@ where
         }
     }
 
-    pub fn get(&mut self, mut p: usize) -> S {
+    pub fn get(&mut self, mut p: usize) -> M::S {
         assert!(p < self.n);
         p += self.size;
         for i in (1..=self.log).rev() {
             self.push(p >> i);
         }
-        self.data[p]
+        self.data[p].clone()
     }
 
-    pub fn prod(&mut self, left: usize, right: usize) -> S {
+    pub fn prod(&mut self, left: usize, right: usize) -> M::S {
         assert!(left <= right);
         assert!(right <= self.n);
         if left == right {
-            return self.e;
+            return self.m.identity();
         }
         let mut l = left + self.size;
         let mut r = right + self.size;
@@ -99,40 +104,40 @@ where
                 self.push(r >> i);
             }
         }
-        let mut sml = self.e;
-        let mut smr = self.e;
+        let mut sml = self.m.identity();
+        let mut smr = self.m.identity();
         while l < r {
             if l & 1 == 1 {
-                sml = (self.op)(sml, self.data[l]);
+                sml = self.m.op(&sml, &self.data[l]);
                 l += 1;
             }
             if r & 1 == 1 {
                 r -= 1;
-                smr = (self.op)(self.data[r], smr);
+                smr = self.m.op(&self.data[r], &smr);
             }
             l >>= 1;
             r >>= 1;
         }
-        (self.op)(sml, smr)
+        self.m.op(&sml, &smr)
     }
 
-    pub fn all_prod(&self) -> S {
-        self.data[1]
+    pub fn all_prod(&self) -> M::S {
+        self.data[1].clone()
     }
 
-    pub fn apply(&mut self, mut p: usize, f: T) {
+    pub fn apply(&mut self, mut p: usize, f: M::F) {
         assert!(p < self.n);
         p += self.size;
         for i in (1..=self.log).rev() {
             self.push(p >> i);
         }
-        self.data[p] = (self.mapping)(f, self.data[p]);
+        self.data[p] = self.m.mapping(&f, &self.data[p]);
         for i in 1..=self.log {
             self.update(p >> i);
         }
     }
 
-    pub fn apply_range(&mut self, left: usize, right: usize, f: T) {
+    pub fn apply_range(&mut self, left: usize, right: usize, f: M::F) {
         assert!(left <= right);
         assert!(right <= self.n);
         if left == right {
@@ -153,12 +158,12 @@ where
             let r2 = r;
             while l < r {
                 if l & 1 != 0 {
-                    self.all_apply(l, f);
+                    self.all_apply(l, &f);
                     l += 1;
                 }
                 if r & 1 != 0 {
                     r -= 1;
-                    self.all_apply(r, f);
+                    self.all_apply(r, &f);
                 }
                 l >>= 1;
                 r >>= 1;
@@ -176,28 +181,111 @@ where
         }
     }
 
+    // the largest r with pred(prod(left, r)), pred(e) must hold
+    pub fn max_right<P>(&mut self, left: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(left <= self.n);
+        assert!(pred(&self.m.identity()));
+        if left == self.n {
+            return self.n;
+        }
+        let mut l = left + self.size;
+        for i in (1..=self.log).rev() {
+            self.push(l >> i);
+        }
+        let mut sm = self.m.identity();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            let res = self.m.op(&sm, &self.data[l]);
+            if !pred(&res) {
+                while l < self.size {
+                    self.push(l);
+                    l *= 2;
+                    let res = self.m.op(&sm, &self.data[l]);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = res;
+            l += 1;
+            if l & l.wrapping_neg() == l {
+                break;
+            }
+        }
+        self.n
+    }
+
+    // the smallest l with pred(prod(l, right)), pred(e) must hold
+    pub fn min_left<P>(&mut self, right: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(right <= self.n);
+        assert!(pred(&self.m.identity()));
+        if right == 0 {
+            return 0;
+        }
+        let mut r = right + self.size;
+        for i in (1..=self.log).rev() {
+            self.push((r - 1) >> i);
+        }
+        let mut sm = self.m.identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            let res = self.m.op(&self.data[r], &sm);
+            if !pred(&res) {
+                while r < self.size {
+                    self.push(r);
+                    r = 2 * r + 1;
+                    let res = self.m.op(&self.data[r], &sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = res;
+            if r & r.wrapping_neg() == r {
+                break;
+            }
+        }
+        0
+    }
+
     fn update(&mut self, k: usize) {
-        self.data[k] = (self.op)(self.data[2 * k], self.data[2 * k + 1]);
+        self.data[k] = self.m.op(&self.data[2 * k], &self.data[2 * k + 1]);
     }
 
-    fn all_apply(&mut self, k: usize, f: T) {
-        self.data[k] = (self.mapping)(f, self.data[k]);
+    fn all_apply(&mut self, k: usize, f: &M::F) {
+        self.data[k] = self.m.mapping(f, &self.data[k]);
         if k < self.size {
-            self.lz[k] = (self.composition)(f, self.lz[k]);
+            self.lz[k] = self.m.composition(f, &self.lz[k]);
         }
     }
 
     fn push(&mut self, k: usize) {
-        self.all_apply(2 * k, self.lz[k]);
-        self.all_apply(2 * k + 1, self.lz[k]);
-        self.lz[k] = self.id;
+        let f = std::mem::replace(&mut self.lz[k], self.m.identity_map());
+        self.all_apply(2 * k, &f);
+        self.all_apply(2 * k + 1, &f);
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_structure::algebra::{Monoid, RangeAddMin};
+    use crate::util::rng::Rng;
 
     const MOD: usize = 998244353;
     #[test]
@@ -241,4 +329,146 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lazy_segtree_binary_search() {
+        let mut rng = Rng::new();
+        for n in 0..20 {
+            let mut a = (0..n).map(|_| rng.next_usize() % 10).collect::<Vec<usize>>();
+            // range add, range (sum, length)
+            let mut st = LazySegtree::from(
+                a.iter().map(|&x| (x, 1)).collect(),
+                |x: (usize, usize), y: (usize, usize)| (x.0 + y.0, x.1 + y.1),
+                (0, 0),
+                |f: usize, x: (usize, usize)| (x.0 + f * x.1, x.1),
+                |f, g| f + g,
+                0,
+            );
+            for _ in 0..50 {
+                let (l, r) = rng.range(n);
+                let f = rng.next_usize() % 3;
+                st.apply_range(l, r, f);
+                a[l..r].iter_mut().for_each(|x| *x += f);
+                let k = rng.next_usize() % 60;
+                for l in 0..=n {
+                    let mut r = l;
+                    while r < n && a[l..=r].iter().sum::<usize>() <= k {
+                        r += 1;
+                    }
+                    assert_eq!(st.max_right(l, |s| s.0 <= k), r);
+                }
+                for r in 0..=n {
+                    let mut l = r;
+                    while l > 0 && a[l - 1..r].iter().sum::<usize>() <= k {
+                        l -= 1;
+                    }
+                    assert_eq!(st.min_left(r, |s| s.0 <= k), l);
+                }
+            }
+        }
+    }
+
+    struct Holder {
+        st: LazySegtree<RangeAddMin>,
+    }
+
+    #[test]
+    fn test_lazy_segtree_map_monoid() {
+        let mut h = Holder {
+            st: LazySegtree::from_monoid(vec![3, 1, 4, 1, 5, 9, 2, 6], RangeAddMin),
+        };
+        assert_eq!(h.st.prod(0, 8), 1);
+        h.st.apply_range(0, 4, 10);
+        assert_eq!(h.st.prod(0, 4), 11);
+        assert_eq!(h.st.prod(2, 7), 2);
+        h.st.apply(6, 5);
+        assert_eq!(h.st.all_prod(), 5);
+        assert_eq!(h.st.max_right(0, |&x| x >= 11), 4);
+
+        let mut trees = [LazySegtree::with_monoid(4, RangeAddMin), LazySegtree::with_monoid(2, RangeAddMin)];
+        trees[1].set(0, 7);
+        trees[1].apply_range(0, 2, -3);
+        assert_eq!(trees[1].prod(0, 2), 4);
+        assert_eq!(trees[0].prod(0, 4), i64::MAX);
+    }
+
+    // values in 0..K, range map through an arbitrary table, range histogram
+    // neither S nor F is Copy
+    const K: usize = 4;
+
+    struct Remap;
+
+    impl Monoid for Remap {
+        type S = Vec<usize>;
+
+        fn identity(&self) -> Vec<usize> {
+            vec![0; K]
+        }
+
+        fn op(&self, a: &Vec<usize>, b: &Vec<usize>) -> Vec<usize> {
+            a.iter().zip(b).map(|(x, y)| x + y).collect()
+        }
+    }
+
+    impl MapMonoid for Remap {
+        type F = Vec<usize>;
+
+        fn identity_map(&self) -> Vec<usize> {
+            (0..K).collect()
+        }
+
+        fn mapping(&self, f: &Vec<usize>, x: &Vec<usize>) -> Vec<usize> {
+            let mut res = vec![0; K];
+            for i in 0..K {
+                res[f[i]] += x[i];
+            }
+            res
+        }
+
+        fn composition(&self, f: &Vec<usize>, g: &Vec<usize>) -> Vec<usize> {
+            g.iter().map(|&i| f[i]).collect()
+        }
+    }
+
+    #[test]
+    fn test_lazy_segtree_clone() {
+        let mut rng = Rng::new();
+        let hist = |a: &[usize]| {
+            let mut h = vec![0; K];
+            a.iter().for_each(|&x| h[x] += 1);
+            h
+        };
+        for n in 0..20 {
+            let mut a = (0..n).map(|_| rng.next_usize() % K).collect::<Vec<usize>>();
+            let mut st = LazySegtree::from_monoid(a.iter().map(|&x| hist(&[x])).collect(), Remap);
+            for _ in 0..100 {
+                let (l, r) = rng.range(n);
+                match rng.next_usize() % 4 {
+                    0 => {
+                        let f = (0..K).map(|_| rng.next_usize() % K).collect::<Vec<usize>>();
+                        st.apply_range(l, r, f.clone());
+                        a[l..r].iter_mut().for_each(|x| *x = f[*x]);
+                    }
+                    1 if l < n => {
+                        let x = rng.next_usize() % K;
+                        st.set(l, hist(&[x]));
+                        a[l] = x;
+                        assert_eq!(st.get(l), hist(&[x]));
+                    }
+                    2 => {
+                        let k = rng.next_usize() % 5;
+                        let mut r = l;
+                        while r < n && hist(&a[l..=r])[0] <= k {
+                            r += 1;
+                        }
+                        assert_eq!(st.max_right(l, |s| s[0] <= k), r);
+                    }
+                    _ => {
+                        assert_eq!(st.prod(l, r), hist(&a[l..r]));
+                    }
+                }
+            }
+            assert_eq!(st.all_prod(), hist(&a));
+        }
+    }
+}
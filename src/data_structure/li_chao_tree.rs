@@ -0,0 +1,377 @@
+use crate::util::binary_search::BinarySearch;
+
+// lines y = a * x + b, a max tree stores negated lines
+// everything is evaluated in i128, so any i64 line at any i64 x is exact
+#[derive(Copy, Clone)]
+struct Line {
+    a: i128,
+    b: i128,
+}
+
+impl Line {
+    fn new(a: i64, b: i64, sign: i128) -> Self {
+        Self { a: a as i128 * sign, b: b as i128 * sign }
+    }
+
+    fn eval(&self, x: i128) -> i128 {
+        self.a * x + self.b
+    }
+}
+
+// over the x coordinates given in advance
+// add_line: O(log n), add_segment: O(log^2 n), query: O(log n)
+pub struct LiChaoTree {
+    xs: Vec<i64>,
+    size: usize,
+    sign: i128,
+    data: Vec<Option<Line>>,
+}
+
+impl LiChaoTree {
+    pub fn new(xs: &[i64]) -> Self {
+        Self::build(xs, 1)
+    }
+
+    pub fn new_max(xs: &[i64]) -> Self {
+        Self::build(xs, -1)
+    }
+
+    fn build(xs: &[i64], sign: i128) -> Self {
+        let mut xs = xs.to_vec();
+        xs.sort();
+        xs.dedup();
+        assert!(!xs.is_empty());
+        let size = xs.len().next_power_of_two();
+        let last = *xs.last().unwrap();
+        xs.resize(size, last);
+        Self {
+            xs,
+            size,
+            sign,
+            data: vec![None; 2 * size],
+        }
+    }
+
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = Line::new(a, b, self.sign);
+        self.insert(1, 0, self.size, line);
+    }
+
+    // the line only exists on l <= x < r
+    pub fn add_segment(&mut self, l: i64, r: i64, a: i64, b: i64) {
+        let line = Line::new(a, b, self.sign);
+        let mut li = self.xs.lower_bound(&l) + self.size;
+        let mut ri = self.xs.lower_bound(&r) + self.size;
+        let mut len = 1;
+        while li < ri {
+            if li & 1 == 1 {
+                let nl = li * len - self.size;
+                self.insert(li, nl, nl + len, line);
+                li += 1;
+            }
+            if ri & 1 == 1 {
+                ri -= 1;
+                let nl = ri * len - self.size;
+                self.insert(ri, nl, nl + len, line);
+            }
+            li >>= 1;
+            ri >>= 1;
+            len <<= 1;
+        }
+    }
+
+    // x must be one of the given coordinates, None if no line covers x
+    pub fn min_at(&self, x: i64) -> Option<i128> {
+        assert!(self.sign == 1);
+        self.query(x)
+    }
+
+    pub fn max_at(&self, x: i64) -> Option<i128> {
+        assert!(self.sign == -1);
+        self.query(x).map(|v| -v)
+    }
+
+    fn query(&self, x: i64) -> Option<i128> {
+        let i = self.xs.lower_bound(&x);
+        assert!(i < self.size && self.xs[i] == x);
+        let mut k = i + self.size;
+        let mut res: Option<i128> = None;
+        while k > 0 {
+            if let Some(line) = self.data[k] {
+                let v = line.eval(x as i128);
+                res = Some(res.map_or(v, |r| r.min(v)));
+            }
+            k >>= 1;
+        }
+        res
+    }
+
+    // node k covers the indices [nl, nr)
+    fn insert(&mut self, mut k: usize, mut nl: usize, mut nr: usize, mut line: Line) {
+        loop {
+            let cur = match self.data[k] {
+                Some(cur) => cur,
+                None => {
+                    self.data[k] = Some(line);
+                    return;
+                }
+            };
+            let mid = (nl + nr) / 2;
+            let (xl, xm, xr) = (self.xs[nl] as i128, self.xs[mid] as i128, self.xs[nr - 1] as i128);
+            if nr - nl == 1 {
+                if line.eval(xl) < cur.eval(xl) {
+                    self.data[k] = Some(line);
+                }
+                return;
+            }
+            if line.eval(xm) < cur.eval(xm) {
+                self.data[k] = Some(line);
+                line = cur;
+            }
+            let kept = self.data[k].unwrap();
+            if line.eval(xl) < kept.eval(xl) {
+                k *= 2;
+                nr = mid;
+            }
+            else if line.eval(xr) < kept.eval(xr) {
+                k = 2 * k + 1;
+                nl = mid;
+            }
+            else {
+                return;
+            }
+        }
+    }
+}
+
+struct Node {
+    line: Option<Line>,
+    ch: [usize; 2],
+}
+
+// over every integer x in [lo, hi), nodes are allocated on demand in one Vec
+// node ranges are kept in i128, so [lo, hi) may be as wide as all of i64
+pub struct DynamicLiChaoTree {
+    lo: i64,
+    hi: i64,
+    sign: i128,
+    nodes: Vec<Node>,
+}
+
+impl DynamicLiChaoTree {
+    pub fn new(lo: i64, hi: i64) -> Self {
+        Self::build(lo, hi, 1)
+    }
+
+    pub fn new_max(lo: i64, hi: i64) -> Self {
+        Self::build(lo, hi, -1)
+    }
+
+    fn build(lo: i64, hi: i64, sign: i128) -> Self {
+        assert!(lo < hi);
+        Self {
+            lo,
+            hi,
+            sign,
+            nodes: vec![Node { line: None, ch: [0, 0] }],
+        }
+    }
+
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = Line::new(a, b, self.sign);
+        self.insert(0, self.lo as i128, self.hi as i128, line);
+    }
+
+    // the line only exists on l <= x < r
+    pub fn add_segment(&mut self, l: i64, r: i64, a: i64, b: i64) {
+        let line = Line::new(a, b, self.sign);
+        let (l, r) = (l.max(self.lo), r.min(self.hi));
+        if l < r {
+            self.add_segment_rec(0, self.lo as i128, self.hi as i128, l as i128, r as i128, line);
+        }
+    }
+
+    pub fn min_at(&self, x: i64) -> Option<i128> {
+        assert!(self.sign == 1);
+        self.query(x)
+    }
+
+    pub fn max_at(&self, x: i64) -> Option<i128> {
+        assert!(self.sign == -1);
+        self.query(x).map(|v| -v)
+    }
+
+    fn query(&self, x: i64) -> Option<i128> {
+        assert!(self.lo <= x && x < self.hi);
+        let x = x as i128;
+        let (mut k, mut nl, mut nr) = (0, self.lo as i128, self.hi as i128);
+        let mut res: Option<i128> = None;
+        loop {
+            if let Some(line) = self.nodes[k].line {
+                let v = line.eval(x);
+                res = Some(res.map_or(v, |r| r.min(v)));
+            }
+            let mid = nl + (nr - nl) / 2;
+            let (c, l, r) = if x < mid {(0, nl, mid)} else {(1, mid, nr)};
+            if self.nodes[k].ch[c] == 0 {
+                return res;
+            }
+            k = self.nodes[k].ch[c];
+            nl = l;
+            nr = r;
+        }
+    }
+
+    fn child(&mut self, k: usize, c: usize) -> usize {
+        if self.nodes[k].ch[c] == 0 {
+            self.nodes.push(Node { line: None, ch: [0, 0] });
+            let id = self.nodes.len() - 1;
+            self.nodes[k].ch[c] = id;
+        }
+        self.nodes[k].ch[c]
+    }
+
+    fn add_segment_rec(&mut self, k: usize, nl: i128, nr: i128, l: i128, r: i128, line: Line) {
+        if r <= nl || nr <= l {
+            return;
+        }
+        if l <= nl && nr <= r {
+            self.insert(k, nl, nr, line);
+            return;
+        }
+        let mid = nl + (nr - nl) / 2;
+        if l < mid {
+            let a = self.child(k, 0);
+            self.add_segment_rec(a, nl, mid, l, r, line);
+        }
+        if mid < r {
+            let b = self.child(k, 1);
+            self.add_segment_rec(b, mid, nr, l, r, line);
+        }
+    }
+
+    // node k covers [nl, nr)
+    fn insert(&mut self, mut k: usize, mut nl: i128, mut nr: i128, mut line: Line) {
+        loop {
+            let cur = match self.nodes[k].line {
+                Some(cur) => cur,
+                None => {
+                    self.nodes[k].line = Some(line);
+                    return;
+                }
+            };
+            if nr - nl == 1 {
+                if line.eval(nl) < cur.eval(nl) {
+                    self.nodes[k].line = Some(line);
+                }
+                return;
+            }
+            let mid = nl + (nr - nl) / 2;
+            if line.eval(mid) < cur.eval(mid) {
+                self.nodes[k].line = Some(line);
+                line = cur;
+            }
+            let kept = self.nodes[k].line.unwrap();
+            if line.eval(nl) < kept.eval(nl) {
+                k = self.child(k, 0);
+                nr = mid;
+            }
+            else if line.eval(nr - 1) < kept.eval(nr - 1) {
+                k = self.child(k, 1);
+                nl = mid;
+            }
+            else {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_li_chao_tree() {
+        let mut rng = Rng::new();
+        let xs = (0..40).map(|_| rng.int(-100, 101)).collect::<Vec<i64>>();
+        let mut min = LiChaoTree::new(&xs);
+        let mut max = LiChaoTree::new_max(&xs);
+        let mut dmin = DynamicLiChaoTree::new(-100, 101);
+        let mut dmax = DynamicLiChaoTree::new_max(-100, 101);
+        // (l, r, a, b)
+        let mut lines: Vec<(i64, i64, i64, i64)> = Vec::new();
+        for _ in 0..300 {
+            let (a, b) = (rng.int(-20, 21), rng.int(-1000, 1001));
+            if rng.next_usize().is_multiple_of(2) {
+                min.add_line(a, b);
+                max.add_line(a, b);
+                dmin.add_line(a, b);
+                dmax.add_line(a, b);
+                lines.push((i64::MIN, i64::MAX, a, b));
+            }
+            else {
+                let (mut l, mut r) = (rng.int(-110, 111), rng.int(-110, 111));
+                if l > r {
+                    std::mem::swap(&mut l, &mut r);
+                }
+                min.add_segment(l, r, a, b);
+                max.add_segment(l, r, a, b);
+                dmin.add_segment(l, r, a, b);
+                dmax.add_segment(l, r, a, b);
+                lines.push((l, r, a, b));
+            }
+            let x = if rng.next_usize().is_multiple_of(2) {xs[rng.next_usize() % xs.len()]} else {rng.int(-100, 101)};
+            let vals = lines.iter().filter(|s| s.0 <= x && x < s.1).map(|s| (s.2 * x + s.3) as i128);
+            let (lo, hi) = (vals.clone().min(), vals.max());
+            if xs.contains(&x) {
+                assert_eq!(min.min_at(x), lo);
+                assert_eq!(max.max_at(x), hi);
+            }
+            assert_eq!(dmin.min_at(x), lo);
+            assert_eq!(dmax.max_at(x), hi);
+        }
+    }
+
+    #[test]
+    fn test_li_chao_tree_huge() {
+        let (lo, hi) = (-1_000_000_000, 1_000_000_001);
+        let mut st = DynamicLiChaoTree::new(lo, hi);
+        assert_eq!(st.min_at(0), None);
+        st.add_line(1_000_000_000, 0);
+        st.add_line(-1_000_000_000, 0);
+        st.add_segment(-5, 5, 0, -7);
+        assert_eq!(st.min_at(lo), Some(-1_000_000_000_000_000_000));
+        assert_eq!(st.min_at(hi - 1), Some(-1_000_000_000_000_000_000));
+        assert_eq!(st.min_at(0), Some(-7));
+        assert_eq!(st.min_at(5), Some(-5_000_000_000));
+    }
+
+    #[test]
+    fn test_li_chao_tree_full_range() {
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        let mut min = DynamicLiChaoTree::new(lo, hi);
+        let mut max = DynamicLiChaoTree::new_max(lo, hi);
+        for (a, b) in [(i64::MIN, i64::MAX), (i64::MAX, i64::MIN), (0, 0), (-3, 5)] {
+            min.add_line(a, b);
+            max.add_line(a, b);
+        }
+        min.add_segment(-10, 10, i64::MIN, 0);
+        max.add_segment(-10, 10, i64::MIN, 0);
+        let lines = [(i64::MIN, i64::MAX), (i64::MAX, i64::MIN), (0, 0), (-3, 5)];
+        for x in [lo, lo + 1, -11, -10, -1, 0, 1, 9, 10, hi - 1] {
+            let mut vals = lines.iter().map(|&(a, b)| a as i128 * x as i128 + b as i128).collect::<Vec<_>>();
+            if (-10..10).contains(&x) {
+                vals.push(i64::MIN as i128 * x as i128);
+            }
+            assert_eq!(min.min_at(x), vals.iter().copied().min());
+            assert_eq!(max.max_at(x), vals.iter().copied().max());
+        }
+
+        let mut st = LiChaoTree::new_max(&[lo, 0, hi]);
+        st.add_line(i64::MIN, i64::MIN);
+        assert_eq!(st.max_at(lo), Some(i64::MIN as i128 * i64::MIN as i128 + i64::MIN as i128));
+        assert_eq!(st.max_at(hi), Some(i64::MIN as i128 * i64::MAX as i128 + i64::MIN as i128));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::util::binary_search::BinarySearch;
+
+// static array, every node keeps its elements sorted
+// fractional cascading: one binary search at the root, then O(1) per node, O(log n) per query
+pub struct MergeSortTree {
+    n: usize,
+    size: usize,
+    vals: Vec<Vec<i64>>,
+    // sums[k][i]: the sum of vals[k][..i]
+    sums: Vec<Vec<i64>>,
+    // to_left[k][i]: how many of vals[k][..i] come from the left child
+    to_left: Vec<Vec<usize>>,
+}
+
+impl From<Vec<i64>> for MergeSortTree {
+    fn from(a: Vec<i64>) -> Self {
+        Self::new(a)
+    }
+}
+
+impl MergeSortTree {
+    pub fn new(a: Vec<i64>) -> Self {
+        let n = a.len();
+        let size = n.next_power_of_two();
+        let mut vals = vec![Vec::new(); 2 * size];
+        let mut to_left = vec![Vec::new(); size];
+        for (i, x) in a.into_iter().enumerate() {
+            vals[size + i] = vec![x];
+        }
+        for k in (1..size).rev() {
+            let (left, right) = (&vals[2 * k], &vals[2 * k + 1]);
+            let mut merged = Vec::with_capacity(left.len() + right.len());
+            let mut cnt = Vec::with_capacity(left.len() + right.len() + 1);
+            let (mut i, mut j) = (0, 0);
+            cnt.push(0);
+            while i < left.len() || j < right.len() {
+                if j == right.len() || (i < left.len() && left[i] <= right[j]) {
+                    merged.push(left[i]);
+                    i += 1;
+                }
+                else {
+                    merged.push(right[j]);
+                    j += 1;
+                }
+                cnt.push(i);
+            }
+            vals[k] = merged;
+            to_left[k] = cnt;
+        }
+        let sums = vals.iter().map(|v| {
+            let mut s = vec![0; v.len() + 1];
+            for i in 0..v.len() {
+                s[i + 1] = s[i] + v[i];
+            }
+            s
+        }).collect();
+        Self {
+            n,
+            size,
+            vals,
+            sums,
+            to_left,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    // the number of elements < x in [l, r)
+    pub fn count_less(&self, l: usize, r: usize, x: i64) -> usize {
+        self.query(l, r, x).0
+    }
+
+    // the sum of elements < x in [l, r)
+    pub fn sum_less(&self, l: usize, r: usize, x: i64) -> i64 {
+        self.query(l, r, x).1
+    }
+
+    // (count, sum) of elements < x in [l, r)
+    fn query(&self, l: usize, r: usize, x: i64) -> (usize, i64) {
+        assert!(l <= r && r <= self.n);
+        if l == r {
+            return (0, 0);
+        }
+        let i = self.vals[1].lower_bound(&x);
+        self.query_rec(1, 0, self.size, l, r, i)
+    }
+
+    // node k covers [nl, nr), i elements of vals[k] are < x
+    fn query_rec(&self, k: usize, nl: usize, nr: usize, l: usize, r: usize, i: usize) -> (usize, i64) {
+        if r <= nl || nr <= l || i == 0 {
+            return (0, 0);
+        }
+        if l <= nl && nr <= r {
+            return (i, self.sums[k][i]);
+        }
+        let mid = (nl + nr) / 2;
+        let il = self.to_left[k][i];
+        let a = self.query_rec(2 * k, nl, mid, l, r, il);
+        let b = self.query_rec(2 * k + 1, mid, nr, l, r, i - il);
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_merge_sort_tree() {
+        let mut rng = Rng::new();
+        for n in 0..40 {
+            let a = (0..n).map(|_| (rng.next_usize() % 41) as i64 - 20).collect::<Vec<i64>>();
+            let mt = MergeSortTree::from(a.clone());
+            assert_eq!(mt.len(), n);
+            for _ in 0..100 {
+                let (l, r) = rng.range(n);
+                let x = (rng.next_usize() % 45) as i64 - 22;
+                let less = a[l..r].iter().filter(|&&v| v < x);
+                assert_eq!(mt.count_less(l, r, x), less.clone().count());
+                assert_eq!(mt.sum_less(l, r, x), less.sum::<i64>());
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use crate::data_structure::rollback_disjoint_set::RollbackDisjointSet;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Same(bool),
+    Components(usize),
+}
+
+#[derive(Copy, Clone)]
+enum Event {
+    Add(usize, usize),
+    Remove(usize, usize),
+    Same(usize, usize),
+    Components,
+}
+
+// O(q log q log n)
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    events: Vec<Event>,
+}
+
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        self.events.push(Event::Add(u.min(v), u.max(v)));
+    }
+
+    // the edge must currently exist (parallel edges are counted)
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        self.events.push(Event::Remove(u.min(v), u.max(v)));
+    }
+
+    pub fn query_same(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        self.events.push(Event::Same(u, v));
+    }
+
+    pub fn query_components(&mut self) {
+        self.events.push(Event::Components);
+    }
+
+    // answers of the queries in the order they were issued
+    pub fn solve(&self) -> Vec<Answer> {
+        let q = self.events.len();
+        if q == 0 {
+            return Vec::new();
+        }
+        let size = q.next_power_of_two();
+        let mut seg = vec![Vec::new(); 2 * size];
+        let mut alive: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (t, &ev) in self.events.iter().enumerate() {
+            match ev {
+                Event::Add(u, v) => alive.entry((u, v)).or_default().push(t),
+                Event::Remove(u, v) => {
+                    let s = alive.get_mut(&(u, v)).and_then(|st| st.pop()).expect("removing a missing edge");
+                    Self::add_interval(&mut seg, size, s, t, (u, v));
+                }
+                _ => {}
+            }
+        }
+        for (&e, st) in alive.iter() {
+            for &s in st {
+                Self::add_interval(&mut seg, size, s, q, e);
+            }
+        }
+
+        let mut uf = RollbackDisjointSet::new(self.n);
+        let mut ans = Vec::new();
+        self.dfs(1, size, &seg, &mut uf, &mut ans);
+        ans
+    }
+
+    fn add_interval(seg: &mut [Vec<(usize, usize)>], size: usize, left: usize, right: usize, e: (usize, usize)) {
+        let mut l = left + size;
+        let mut r = right + size;
+        while l < r {
+            if l & 1 == 1 {
+                seg[l].push(e);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                seg[r].push(e);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+    }
+
+    fn dfs(&self, k: usize, size: usize, seg: &[Vec<(usize, usize)>], uf: &mut RollbackDisjointSet, ans: &mut Vec<Answer>) {
+        if k >= size && k - size >= self.events.len() {
+            return;
+        }
+        let snapshot = uf.snapshot();
+        for &(u, v) in &seg[k] {
+            uf.unite(u, v);
+        }
+        if k < size {
+            self.dfs(2 * k, size, seg, uf, ans);
+            self.dfs(2 * k + 1, size, seg, uf, ans);
+        }
+        else {
+            match self.events[k - size] {
+                Event::Same(u, v) => ans.push(Answer::Same(uf.same(u, v))),
+                Event::Components => ans.push(Answer::Components(uf.num_trees())),
+                _ => {}
+            }
+        }
+        uf.rollback(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+    use crate::data_structure::disjoint_set::DisjointSet;
+
+    #[test]
+    fn dynamic_connectivity_test() {
+        let mut dc = OfflineDynamicConnectivity::new(4);
+        dc.add_edge(0, 1);
+        dc.add_edge(1, 2);
+        dc.query_same(0, 2);
+        dc.query_components();
+        dc.remove_edge(2, 1);
+        dc.query_same(0, 2);
+        dc.add_edge(0, 1);
+        dc.remove_edge(0, 1);
+        dc.query_same(0, 1);
+        dc.query_components();
+        let ans = dc.solve();
+        assert_eq!(ans, vec![
+            Answer::Same(true),
+            Answer::Components(2),
+            Answer::Same(false),
+            Answer::Same(true),
+            Answer::Components(3),
+        ]);
+    }
+
+    #[test]
+    fn dynamic_connectivity_random_test() {
+        let n = 8;
+        let mut rng = Rng::new();
+        let mut dc = OfflineDynamicConnectivity::new(n);
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut expected = Vec::new();
+        for _ in 0..500 {
+            match rng.next_usize() % 4 {
+                0 => {
+                    let (u, v) = (rng.next_usize() % n, rng.next_usize() % n);
+                    dc.add_edge(u, v);
+                    edges.push((u, v));
+                }
+                1 if !edges.is_empty() => {
+                    let (u, v) = edges.swap_remove(rng.next_usize() % edges.len());
+                    dc.remove_edge(v, u);
+                }
+                _ => {
+                    let mut uf = DisjointSet::new(n);
+                    for &(u, v) in &edges {
+                        uf.unite(u, v);
+                    }
+                    let (u, v) = (rng.next_usize() % n, rng.next_usize() % n);
+                    dc.query_same(u, v);
+                    dc.query_components();
+                    expected.push(Answer::Same(uf.same(u, v)));
+                    expected.push(Answer::Components(uf.num_trees()));
+                }
+            }
+        }
+        assert_eq!(dc.solve(), expected);
+    }
+}
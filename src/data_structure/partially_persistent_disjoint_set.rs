@@ -0,0 +1,150 @@
+// the t-th call of unite (1-indexed) happens at time t
+// time t means the state after the first t unites
+pub struct PartiallyPersistentDisjointSet {
+    now: usize,
+    data: Vec<usize>,
+    size: Vec<usize>,
+    time: Vec<usize>,
+}
+
+impl PartiallyPersistentDisjointSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            now: 0,
+            data: (0..n).collect::<Vec<usize>>(),
+            size: vec![1; n],
+            time: vec![usize::MAX; n],
+        }
+    }
+
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    // root of x at time t
+    pub fn find(&self, mut x: usize, t: usize) -> usize {
+        while self.time[x] <= t {
+            x = self.data[x];
+        }
+        x
+    }
+
+    // returns true if two different trees were merged
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        self.now += 1;
+        let mut px = self.find(x, self.now);
+        let mut py = self.find(y, self.now);
+        if px == py {
+            return false;
+        }
+        if self.size[py] < self.size[px] {
+            std::mem::swap(&mut px, &mut py);
+        }
+        self.data[px] = py;
+        self.size[py] += self.size[px];
+        self.time[px] = self.now;
+        true
+    }
+
+    pub fn same_at(&self, x: usize, y: usize, t: usize) -> bool {
+        self.find(x, t) == self.find(y, t)
+    }
+
+    pub fn same(&self, x: usize, y: usize) -> bool {
+        self.same_at(x, y, self.now)
+    }
+
+    // the smallest t with same_at(x, y, t)
+    pub fn first_connected_time(&self, mut x: usize, mut y: usize) -> Option<usize> {
+        if !self.same(x, y) {
+            return None;
+        }
+        let mut t = 0;
+        while x != y {
+            if self.time[x] < self.time[y] {
+                t = self.time[x];
+                x = self.data[x];
+            }
+            else {
+                t = self.time[y];
+                y = self.data[y];
+            }
+        }
+        Some(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persistent_dsu_test() {
+        let mut uf = PartiallyPersistentDisjointSet::new(5);
+        assert!(uf.unite(0, 1));
+        assert!(uf.unite(2, 3));
+        assert!(!uf.unite(1, 0));
+        assert!(uf.unite(1, 3));
+        assert_eq!(uf.now(), 4);
+        assert!(!uf.same_at(0, 1, 0));
+        assert!(uf.same_at(0, 1, 1));
+        assert!(!uf.same_at(0, 2, 3));
+        assert!(uf.same_at(0, 2, 4));
+        assert!(!uf.same(0, 4));
+        assert_eq!(uf.first_connected_time(0, 0), Some(0));
+        assert_eq!(uf.first_connected_time(1, 0), Some(1));
+        assert_eq!(uf.first_connected_time(3, 2), Some(2));
+        assert_eq!(uf.first_connected_time(0, 3), Some(4));
+        assert_eq!(uf.first_connected_time(0, 4), None);
+    }
+
+    #[test]
+    fn bottleneck_test() {
+        let mut edges = vec![
+            (1, 1, 0),
+            (3, 2, 0),
+            (1, 2, 1),
+            (7, 3, 1),
+            (1, 4, 2),
+            (3, 1, 4),
+            (1, 3, 4),
+            (1, 3, 5),
+            (6, 4, 5),
+        ];
+        edges.sort();
+        let mut uf = PartiallyPersistentDisjointSet::new(6);
+        for &(_, v1, v2) in &edges {
+            uf.unite(v1, v2);
+        }
+        // minimax path weight between two vertices
+        let bottleneck = |x, y| uf.first_connected_time(x, y).map(|t| edges[t - 1].0);
+        assert_eq!(bottleneck(0, 5), Some(1));
+        assert_eq!(bottleneck(1, 3), Some(1));
+        for x in 0..6 {
+            for y in 0..6 {
+                // brute force: smallest prefix of sorted edges that connects x and y
+                let mut expected = None;
+                for k in 0..=edges.len() {
+                    let mut reach = vec![x];
+                    let mut seen = [false; 6];
+                    seen[x] = true;
+                    while let Some(v) = reach.pop() {
+                        for &(_, a, b) in &edges[..k] {
+                            for (s, d) in [(a, b), (b, a)] {
+                                if s == v && !seen[d] {
+                                    seen[d] = true;
+                                    reach.push(d);
+                                }
+                            }
+                        }
+                    }
+                    if seen[y] {
+                        expected = Some(k);
+                        break;
+                    }
+                }
+                assert_eq!(uf.first_connected_time(x, y), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use crate::data_structure::algebra::Monoid;
+
+struct Node<S> {
+    val: S,
+    left: usize,
+    right: usize,
+}
+
+// every set creates a new version, a version is the index of its root node
+// all nodes live in one Vec, O(log n) new nodes per set
+pub struct PersistentSegtree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    init: usize,
+    nodes: Vec<Node<M::S>>,
+    m: M,
+}
+
+impl<M> PersistentSegtree<M>
+where
+    M: Monoid,
+{
+    pub fn with_monoid(n: usize, m: M) -> Self {
+        let e = m.identity();
+        Self::from_monoid(vec![e; n], m)
+    }
+
+    pub fn from_monoid(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let mut ret = Self {
+            n,
+            init: 0,
+            nodes: Vec::with_capacity(2 * n.max(1)),
+            m,
+        };
+        ret.init = if n == 0 {
+            let e = ret.m.identity();
+            ret.new_node(e, 0, 0)
+        }
+        else {
+            ret.build(0, n, &v)
+        };
+        ret
+    }
+
+    // version of the initial array
+    pub fn initial(&self) -> usize {
+        self.init
+    }
+
+    pub fn set(&mut self, version: usize, p: usize, x: M::S) -> usize {
+        assert!(p < self.n);
+        self.set_rec(version, 0, self.n, p, x)
+    }
+
+    pub fn get(&self, version: usize, p: usize) -> M::S {
+        assert!(p < self.n);
+        let (mut k, mut l, mut r) = (version, 0, self.n);
+        while r - l > 1 {
+            let mid = (l + r) / 2;
+            if p < mid {
+                k = self.nodes[k].left;
+                r = mid;
+            }
+            else {
+                k = self.nodes[k].right;
+                l = mid;
+            }
+        }
+        self.nodes[k].val.clone()
+    }
+
+    pub fn prod(&self, version: usize, left: usize, right: usize) -> M::S {
+        assert!(left <= right);
+        assert!(right <= self.n);
+        if left == right {
+            return self.m.identity();
+        }
+        self.prod_rec(version, 0, self.n, left, right)
+    }
+
+    pub fn all_prod(&self, version: usize) -> M::S {
+        self.nodes[version].val.clone()
+    }
+
+    // the largest r with pred(prod(version, left, r)), pred(e) must hold
+    pub fn max_right<P>(&self, version: usize, left: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        self.descend([version], left, |s| pred(&s[0]))
+    }
+
+    // the largest r with pred(prod(va, left, r), prod(vb, left, r)), pred(e, e) must hold
+    // e.g. the k-th smallest of a range from two versions of a counting tree
+    pub fn max_right_pair<P>(&self, va: usize, vb: usize, left: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S, &M::S) -> bool,
+    {
+        self.descend([va, vb], left, |s| pred(&s[0], &s[1]))
+    }
+
+    // walks K versions side by side, O(K log n)
+    fn descend<const K: usize, P>(&self, versions: [usize; K], left: usize, pred: P) -> usize
+    where
+        P: Fn(&[M::S; K]) -> bool,
+    {
+        assert!(left <= self.n);
+        let mut sm: [M::S; K] = std::array::from_fn(|_| self.m.identity());
+        assert!(pred(&sm));
+        if left == self.n {
+            return self.n;
+        }
+        self.descend_rec(versions, 0, self.n, left, &pred, &mut sm).unwrap_or(self.n)
+    }
+
+    // the first position in [left, ..) where pred fails, if any
+    fn descend_rec<const K: usize, P>(&self, ks: [usize; K], l: usize, r: usize, left: usize, pred: &P, sm: &mut [M::S; K]) -> Option<usize>
+    where
+        P: Fn(&[M::S; K]) -> bool,
+    {
+        if r <= left {
+            return None;
+        }
+        if left <= l {
+            let res = std::array::from_fn(|i| self.m.op(&sm[i], &self.nodes[ks[i]].val));
+            if pred(&res) {
+                *sm = res;
+                return None;
+            }
+            if r - l == 1 {
+                return Some(l);
+            }
+        }
+        let mid = (l + r) / 2;
+        let (a, b) = (ks.map(|k| self.nodes[k].left), ks.map(|k| self.nodes[k].right));
+        self.descend_rec(a, l, mid, left, pred, sm)
+            .or_else(|| self.descend_rec(b, mid, r, left, pred, sm))
+    }
+
+    fn new_node(&mut self, val: M::S, left: usize, right: usize) -> usize {
+        self.nodes.push(Node { val, left, right });
+        self.nodes.len() - 1
+    }
+
+    fn build(&mut self, l: usize, r: usize, v: &[M::S]) -> usize {
+        if r - l == 1 {
+            return self.new_node(v[l].clone(), 0, 0);
+        }
+        let mid = (l + r) / 2;
+        let a = self.build(l, mid, v);
+        let b = self.build(mid, r, v);
+        let val = self.m.op(&self.nodes[a].val, &self.nodes[b].val);
+        self.new_node(val, a, b)
+    }
+
+    fn set_rec(&mut self, k: usize, l: usize, r: usize, p: usize, x: M::S) -> usize {
+        if r - l == 1 {
+            return self.new_node(x, 0, 0);
+        }
+        let mid = (l + r) / 2;
+        let (mut a, mut b) = (self.nodes[k].left, self.nodes[k].right);
+        if p < mid {
+            a = self.set_rec(a, l, mid, p, x);
+        }
+        else {
+            b = self.set_rec(b, mid, r, p, x);
+        }
+        let val = self.m.op(&self.nodes[a].val, &self.nodes[b].val);
+        self.new_node(val, a, b)
+    }
+
+    fn prod_rec(&self, k: usize, l: usize, r: usize, a: usize, b: usize) -> M::S {
+        if b <= l || r <= a {
+            return self.m.identity();
+        }
+        if a <= l && r <= b {
+            return self.nodes[k].val.clone();
+        }
+        let mid = (l + r) / 2;
+        let x = self.prod_rec(self.nodes[k].left, l, mid, a, b);
+        let y = self.prod_rec(self.nodes[k].right, mid, r, a, b);
+        self.m.op(&x, &y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    struct Sum;
+
+    impl Monoid for Sum {
+        type S = i64;
+
+        fn identity(&self) -> i64 {
+            0
+        }
+
+        fn op(&self, a: &i64, b: &i64) -> i64 {
+            a + b
+        }
+    }
+
+    #[test]
+    fn test_persistent_segtree() {
+        let mut rng = Rng::new();
+        let n = 13;
+        let mut st = PersistentSegtree::from_monoid((0..n as i64).collect(), Sum);
+        let mut versions = vec![st.initial()];
+        let mut arrays = vec![(0..n as i64).collect::<Vec<i64>>()];
+        for _ in 0..300 {
+            let v = rng.next_usize() % versions.len();
+            let p = rng.next_usize() % n;
+            let x = (rng.next_usize() % 100) as i64;
+            versions.push(st.set(versions[v], p, x));
+            let mut a = arrays[v].clone();
+            a[p] = x;
+            arrays.push(a);
+            let q = rng.next_usize() % versions.len();
+            let (l, r) = rng.range(n);
+            assert_eq!(st.prod(versions[q], l, r), arrays[q][l..r].iter().sum::<i64>());
+            assert_eq!(st.all_prod(versions[q]), arrays[q].iter().sum::<i64>());
+            if l < n {
+                assert_eq!(st.get(versions[q], l), arrays[q][l]);
+            }
+            let k = (rng.next_usize() % 300) as i64;
+            let mut r = l;
+            while r < n && arrays[q][l..=r].iter().sum::<i64>() <= k {
+                r += 1;
+            }
+            assert_eq!(st.max_right(versions[q], l, |&s| s <= k), r);
+        }
+    }
+
+    #[test]
+    fn test_range_kth_smallest() {
+        let a = [5, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let mut vals = a.to_vec();
+        vals.sort();
+        vals.dedup();
+        // version i counts the values of a[..i]
+        let mut st = PersistentSegtree::with_monoid(vals.len(), Sum);
+        let mut versions = vec![st.initial()];
+        for &x in &a {
+            let p = vals.binary_search(&x).unwrap();
+            let last = *versions.last().unwrap();
+            let c = st.get(last, p);
+            versions.push(st.set(last, p, c + 1));
+        }
+        // the first value whose prefix count in a[l..r] exceeds k
+        let kth = |l: usize, r: usize, k: i64| {
+            vals[st.max_right_pair(versions[l], versions[r], 0, |x, y| y - x <= k)]
+        };
+        for l in 0..a.len() {
+            for r in l + 1..=a.len() {
+                let mut b = a[l..r].to_vec();
+                b.sort();
+                for (k, &x) in b.iter().enumerate() {
+                    assert_eq!(kth(l, r, k as i64), x);
+                }
+            }
+        }
+    }
+}
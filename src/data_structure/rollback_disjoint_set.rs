@@ -0,0 +1,109 @@
+// union by size without path compression, so every unite can be undone
+pub struct RollbackDisjointSet {
+    _n: usize,
+    data: Vec<usize>,
+    size: Vec<usize>,
+    history: Vec<Option<(usize, usize)>>,
+}
+
+impl RollbackDisjointSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            _n: n,
+            data: (0..n).collect::<Vec<usize>>(),
+            size: vec![1; n],
+            history: Vec::new(),
+        }
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.data[x] != x {
+            x = self.data[x];
+        }
+        x
+    }
+
+    // returns true if two different trees were merged
+    pub fn unite(&mut self, x: usize, y: usize) -> bool {
+        let mut px = self.find(x);
+        let mut py = self.find(y);
+        if px == py {
+            self.history.push(None);
+            return false;
+        }
+        if self.size[py] < self.size[px] {
+            std::mem::swap(&mut px, &mut py);
+        }
+        self.data[px] = py;
+        self.size[py] += self.size[px];
+        self._n -= 1;
+        self.history.push(Some((px, py)));
+        true
+    }
+
+    // reverts the last unite (including the ones that merged nothing)
+    pub fn undo(&mut self) {
+        if let Some((px, py)) = self.history.pop().flatten() {
+            self.data[px] = px;
+            self.size[py] -= self.size[px];
+            self._n += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len());
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+
+    pub fn same(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn num_trees(&self) -> usize {
+        self._n
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_dsu_test() {
+        let mut uf = RollbackDisjointSet::new(5);
+        assert!(uf.unite(0, 1));
+        let s = uf.snapshot();
+        assert!(uf.unite(1, 2));
+        assert!(!uf.unite(0, 2));
+        assert!(uf.unite(3, 4));
+        assert_eq!(uf.size(2), 3);
+        assert_eq!(uf.num_trees(), 2);
+
+        uf.undo();
+        assert!(!uf.same(3, 4));
+        assert!(uf.same(0, 2));
+        uf.undo();
+        assert!(uf.same(0, 2));
+
+        uf.rollback(s);
+        assert!(uf.same(0, 1));
+        assert!(!uf.same(0, 2));
+        assert_eq!(uf.size(0), 2);
+        assert_eq!(uf.size(2), 1);
+        assert_eq!(uf.num_trees(), 4);
+
+        uf.rollback(0);
+        assert!(!uf.same(0, 1));
+        assert_eq!(uf.num_trees(), 5);
+    }
+}
@@ -0,0 +1,273 @@
+use crate::data_structure::algebra::{ClosureMonoid, Monoid};
+
+pub struct Segtree<M>
+where
+    M: Monoid,
+{
+    n: usize,
+    size: usize,
+    log: usize,
+    data: Vec<M::S>,
+    m: M,
+}
+
+impl<S, F> Segtree<ClosureMonoid<S, F>>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    pub fn new(n: usize, op: F, e: S) -> Self {
+        Self::with_monoid(n, ClosureMonoid::new(op, e))
+    }
+
+    pub fn from(v: Vec<S>, op: F, e: S) -> Self {
+        Self::from_monoid(v, ClosureMonoid::new(op, e))
+    }
+}
+
+impl<M> Segtree<M>
+where
+    M: Monoid,
+{
+    pub fn with_monoid(n: usize, m: M) -> Self {
+        let size = n.next_power_of_two();
+        let log = size.trailing_zeros() as usize;
+        Self {
+            n,
+            size,
+            log,
+            data: vec![m.identity(); size * 2],
+            m,
+        }
+    }
+
+    pub fn from_monoid(v: Vec<M::S>, m: M) -> Self {
+        let n = v.len();
+        let size = n.next_power_of_two();
+        let log = size.trailing_zeros() as usize;
+        let mut data = vec![m.identity(); 2 * size];
+        data[size..(size + n)].clone_from_slice(&v);
+        let mut ret = Self {
+            n,
+            size,
+            log,
+            data,
+            m,
+        };
+        for i in (1..size).rev() {
+            ret.update(i);
+        }
+        ret
+    }
+
+    pub fn set(&mut self, mut p: usize, x: M::S) {
+        assert!(p < self.n);
+        p += self.size;
+        self.data[p] = x;
+        for i in 1..=self.log {
+            self.update(p >> i);
+        }
+    }
+
+    pub fn get(&self, p: usize) -> M::S {
+        assert!(p < self.n);
+        self.data[p + self.size].clone()
+    }
+
+    pub fn prod(&self, left: usize, right: usize) -> M::S {
+        assert!(left <= right);
+        assert!(right <= self.n);
+        let mut sml = self.m.identity();
+        let mut smr = self.m.identity();
+        let mut l = left + self.size;
+        let mut r = right + self.size;
+        while l < r {
+            if l & 1 == 1 {
+                sml = self.m.op(&sml, &self.data[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                smr = self.m.op(&self.data[r], &smr);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        self.m.op(&sml, &smr)
+    }
+
+    pub fn all_prod(&self) -> M::S {
+        self.data[1].clone()
+    }
+
+    // the largest r with pred(prod(left, r)), pred(e) must hold
+    pub fn max_right<P>(&self, left: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(left <= self.n);
+        assert!(pred(&self.m.identity()));
+        if left == self.n {
+            return self.n;
+        }
+        let mut l = left + self.size;
+        let mut sm = self.m.identity();
+        loop {
+            while l & 1 == 0 {
+                l >>= 1;
+            }
+            let res = self.m.op(&sm, &self.data[l]);
+            if !pred(&res) {
+                while l < self.size {
+                    l *= 2;
+                    let res = self.m.op(&sm, &self.data[l]);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.size;
+            }
+            sm = res;
+            l += 1;
+            if l & l.wrapping_neg() == l {
+                break;
+            }
+        }
+        self.n
+    }
+
+    // the smallest l with pred(prod(l, right)), pred(e) must hold
+    pub fn min_left<P>(&self, right: usize, pred: P) -> usize
+    where
+        P: Fn(&M::S) -> bool,
+    {
+        assert!(right <= self.n);
+        assert!(pred(&self.m.identity()));
+        if right == 0 {
+            return 0;
+        }
+        let mut r = right + self.size;
+        let mut sm = self.m.identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1;
+            }
+            let res = self.m.op(&self.data[r], &sm);
+            if !pred(&res) {
+                while r < self.size {
+                    r = 2 * r + 1;
+                    let res = self.m.op(&self.data[r], &sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.size;
+            }
+            sm = res;
+            if r & r.wrapping_neg() == r {
+                break;
+            }
+        }
+        0
+    }
+
+    fn update(&mut self, k: usize) {
+        self.data[k] = self.m.op(&self.data[2 * k], &self.data[2 * k + 1]);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_segtree() {
+        let mut st = Segtree::from(vec![5, 3, 7, 9, 6, 4, 1, 2], |x: i32, y: i32| x.min(y), i32::MAX);
+        assert_eq!(st.prod(0, 8), 1);
+        assert_eq!(st.prod(0, 3), 3);
+        assert_eq!(st.prod(2, 4), 7);
+        assert_eq!(st.prod(3, 3), i32::MAX);
+        st.set(6, 10);
+        assert_eq!(st.get(6), 10);
+        assert_eq!(st.prod(4, 8), 2);
+        assert_eq!(st.all_prod(), 2);
+        assert_eq!(st.max_right(2, |&x| x >= 6), 5);
+        assert_eq!(st.min_left(7, |&x| x >= 4), 2);
+    }
+
+    #[test]
+    fn test_segtree_binary_search() {
+        let mut rng = Rng::new();
+        for n in 0..20 {
+            let mut a = (0..n).map(|_| rng.next_usize() % 10).collect::<Vec<usize>>();
+            let mut st = Segtree::new(n, |x: usize, y: usize| x + y, 0);
+            for (i, &x) in a.iter().enumerate() {
+                st.set(i, x);
+            }
+            for _ in 0..50 {
+                if n > 0 {
+                    let p = rng.next_usize() % n;
+                    a[p] = rng.next_usize() % 10;
+                    st.set(p, a[p]);
+                }
+                let k = rng.next_usize() % 40;
+                for l in 0..=n {
+                    let mut r = l;
+                    while r < n && a[l..=r].iter().sum::<usize>() <= k {
+                        r += 1;
+                    }
+                    assert_eq!(st.max_right(l, |&s| s <= k), r);
+                    assert_eq!(st.prod(l, r), a[l..r].iter().sum::<usize>());
+                }
+                for r in 0..=n {
+                    let mut l = r;
+                    while l > 0 && a[l - 1..r].iter().sum::<usize>() <= k {
+                        l -= 1;
+                    }
+                    assert_eq!(st.min_left(r, |&s| s <= k), l);
+                }
+            }
+        }
+    }
+
+    // 2x2 matrices mod p stored as Vec, the product is not commutative
+    struct MatMul;
+
+    const MOD: u64 = 998244353;
+
+    impl Monoid for MatMul {
+        type S = Vec<u64>;
+
+        fn identity(&self) -> Vec<u64> {
+            vec![1, 0, 0, 1]
+        }
+
+        fn op(&self, a: &Vec<u64>, b: &Vec<u64>) -> Vec<u64> {
+            vec![
+                (a[0] * b[0] + a[1] * b[2]) % MOD,
+                (a[0] * b[1] + a[1] * b[3]) % MOD,
+                (a[2] * b[0] + a[3] * b[2]) % MOD,
+                (a[2] * b[1] + a[3] * b[3]) % MOD,
+            ]
+        }
+    }
+
+    #[test]
+    fn test_segtree_clone() {
+        let a = (0..10u64).map(|i| vec![i, i + 1, 2 * i + 1, 3]).collect::<Vec<_>>();
+        let mut st = Segtree::from_monoid(a.clone(), MatMul);
+        for l in 0..=a.len() {
+            for r in l..=a.len() {
+                assert_eq!(st.prod(l, r), a[l..r].iter().fold(MatMul.identity(), |acc, x| MatMul.op(&acc, x)));
+            }
+        }
+        st.set(3, vec![0, 1, 1, 0]);
+        assert_eq!(st.get(3), vec![0, 1, 1, 0]);
+        assert_eq!(st.prod(3, 5), vec![a[4][2], a[4][3], a[4][0], a[4][1]]);
+    }
+}
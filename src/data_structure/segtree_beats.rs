@@ -0,0 +1,289 @@
+const NEG: i64 = i64::MIN;
+const POS: i64 = i64::MAX;
+
+// range chmin / chmax / add, range sum / min / max
+// amortized O(log^2 n)
+pub struct SegtreeBeats {
+    n: usize,
+    max1: Vec<i64>,
+    max2: Vec<i64>,
+    maxc: Vec<i64>,
+    min1: Vec<i64>,
+    min2: Vec<i64>,
+    minc: Vec<i64>,
+    sum: Vec<i64>,
+    len: Vec<i64>,
+    lz: Vec<i64>,
+}
+
+impl SegtreeBeats {
+    pub fn new(n: usize) -> Self {
+        Self::from(&vec![0; n])
+    }
+
+    pub fn from(v: &[i64]) -> Self {
+        let n = v.len();
+        let m = 4 * n.max(1);
+        let mut ret = Self {
+            n,
+            max1: vec![NEG; m],
+            max2: vec![NEG; m],
+            maxc: vec![0; m],
+            min1: vec![POS; m],
+            min2: vec![POS; m],
+            minc: vec![0; m],
+            sum: vec![0; m],
+            len: vec![0; m],
+            lz: vec![0; m],
+        };
+        if n > 0 {
+            ret.build(1, 0, n, v);
+        }
+        ret
+    }
+
+    // a[i] = min(a[i], x) for i in [l, r)
+    pub fn chmin(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        if l < r {
+            self.chmin_rec(l, r, x, 1, 0, self.n);
+        }
+    }
+
+    // a[i] = max(a[i], x) for i in [l, r)
+    pub fn chmax(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        if l < r {
+            self.chmax_rec(l, r, x, 1, 0, self.n);
+        }
+    }
+
+    // a[i] += x for i in [l, r)
+    pub fn add(&mut self, l: usize, r: usize, x: i64) {
+        assert!(l <= r && r <= self.n);
+        if l < r {
+            self.add_rec(l, r, x, 1, 0, self.n);
+        }
+    }
+
+    pub fn sum(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query(l, r, 1, 0, self.n).sum
+    }
+
+    pub fn min(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query(l, r, 1, 0, self.n).min1
+    }
+
+    pub fn max(&mut self, l: usize, r: usize) -> i64 {
+        assert!(l <= r && r <= self.n);
+        self.query(l, r, 1, 0, self.n).max1
+    }
+
+    fn build(&mut self, k: usize, l: usize, r: usize, v: &[i64]) {
+        if r - l == 1 {
+            self.max1[k] = v[l];
+            self.min1[k] = v[l];
+            self.maxc[k] = 1;
+            self.minc[k] = 1;
+            self.sum[k] = v[l];
+            self.len[k] = 1;
+            return;
+        }
+        let m = (l + r) / 2;
+        self.build(2 * k, l, m, v);
+        self.build(2 * k + 1, m, r, v);
+        self.update(k);
+    }
+
+    fn query(&mut self, a: usize, b: usize, k: usize, l: usize, r: usize) -> Node {
+        if b <= l || r <= a {
+            return Node { sum: 0, min1: POS, max1: NEG };
+        }
+        if a <= l && r <= b {
+            return Node { sum: self.sum[k], min1: self.min1[k], max1: self.max1[k] };
+        }
+        self.push(k);
+        let m = (l + r) / 2;
+        let x = self.query(a, b, 2 * k, l, m);
+        let y = self.query(a, b, 2 * k + 1, m, r);
+        Node { sum: x.sum + y.sum, min1: x.min1.min(y.min1), max1: x.max1.max(y.max1) }
+    }
+
+    fn chmin_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || self.max1[k] <= x {
+            return;
+        }
+        if a <= l && r <= b && self.max2[k] < x {
+            self.update_node_max(k, x);
+            return;
+        }
+        self.push(k);
+        let m = (l + r) / 2;
+        self.chmin_rec(a, b, x, 2 * k, l, m);
+        self.chmin_rec(a, b, x, 2 * k + 1, m, r);
+        self.update(k);
+    }
+
+    fn chmax_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || self.min1[k] >= x {
+            return;
+        }
+        if a <= l && r <= b && self.min2[k] > x {
+            self.update_node_min(k, x);
+            return;
+        }
+        self.push(k);
+        let m = (l + r) / 2;
+        self.chmax_rec(a, b, x, 2 * k, l, m);
+        self.chmax_rec(a, b, x, 2 * k + 1, m, r);
+        self.update(k);
+    }
+
+    fn add_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a {
+            return;
+        }
+        if a <= l && r <= b {
+            self.add_all(k, x);
+            return;
+        }
+        self.push(k);
+        let m = (l + r) / 2;
+        self.add_rec(a, b, x, 2 * k, l, m);
+        self.add_rec(a, b, x, 2 * k + 1, m, r);
+        self.update(k);
+    }
+
+    // max2[k] < x < max1[k]
+    fn update_node_max(&mut self, k: usize, x: i64) {
+        self.sum[k] += (x - self.max1[k]) * self.maxc[k];
+        if self.max1[k] == self.min1[k] {
+            self.min1[k] = x;
+        }
+        else if self.max1[k] == self.min2[k] {
+            self.min2[k] = x;
+        }
+        self.max1[k] = x;
+    }
+
+    // min1[k] < x < min2[k]
+    fn update_node_min(&mut self, k: usize, x: i64) {
+        self.sum[k] += (x - self.min1[k]) * self.minc[k];
+        if self.min1[k] == self.max1[k] {
+            self.max1[k] = x;
+        }
+        else if self.min1[k] == self.max2[k] {
+            self.max2[k] = x;
+        }
+        self.min1[k] = x;
+    }
+
+    fn add_all(&mut self, k: usize, x: i64) {
+        self.max1[k] += x;
+        if self.max2[k] != NEG {
+            self.max2[k] += x;
+        }
+        self.min1[k] += x;
+        if self.min2[k] != POS {
+            self.min2[k] += x;
+        }
+        self.sum[k] += x * self.len[k];
+        self.lz[k] += x;
+    }
+
+    fn push(&mut self, k: usize) {
+        if self.lz[k] != 0 {
+            let x = self.lz[k];
+            self.add_all(2 * k, x);
+            self.add_all(2 * k + 1, x);
+            self.lz[k] = 0;
+        }
+        for c in [2 * k, 2 * k + 1] {
+            if self.max1[c] > self.max1[k] {
+                self.update_node_max(c, self.max1[k]);
+            }
+            if self.min1[c] < self.min1[k] {
+                self.update_node_min(c, self.min1[k]);
+            }
+        }
+    }
+
+    fn update(&mut self, k: usize) {
+        let (a, b) = (2 * k, 2 * k + 1);
+        self.sum[k] = self.sum[a] + self.sum[b];
+        self.len[k] = self.len[a] + self.len[b];
+
+        if self.max1[a] == self.max1[b] {
+            self.max1[k] = self.max1[a];
+            self.max2[k] = self.max2[a].max(self.max2[b]);
+            self.maxc[k] = self.maxc[a] + self.maxc[b];
+        }
+        else {
+            let (hi, lo) = if self.max1[a] > self.max1[b] {(a, b)} else {(b, a)};
+            self.max1[k] = self.max1[hi];
+            self.max2[k] = self.max2[hi].max(self.max1[lo]);
+            self.maxc[k] = self.maxc[hi];
+        }
+
+        if self.min1[a] == self.min1[b] {
+            self.min1[k] = self.min1[a];
+            self.min2[k] = self.min2[a].min(self.min2[b]);
+            self.minc[k] = self.minc[a] + self.minc[b];
+        }
+        else {
+            let (lo, hi) = if self.min1[a] < self.min1[b] {(a, b)} else {(b, a)};
+            self.min1[k] = self.min1[lo];
+            self.min2[k] = self.min2[lo].min(self.min1[hi]);
+            self.minc[k] = self.minc[lo];
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Node {
+    sum: i64,
+    min1: i64,
+    max1: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_segtree_beats() {
+        let mut rng = Rng::new();
+        for n in 1..40 {
+            let mut a = (0..n).map(|_| (rng.next_usize() % 201) as i64 - 100).collect::<Vec<i64>>();
+            let mut st = SegtreeBeats::from(&a);
+            for _ in 0..200 {
+                let (l, r) = rng.range(n);
+                let x = (rng.next_usize() % 201) as i64 - 100;
+                match rng.next_usize() % 4 {
+                    0 => {
+                        st.chmin(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                    }
+                    1 => {
+                        st.chmax(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                    }
+                    2 => {
+                        st.add(l, r, x);
+                        a[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    _ => {
+                        assert_eq!(st.sum(l, r), a[l..r].iter().sum::<i64>());
+                        assert_eq!(st.min(l, r), a[l..r].iter().copied().min().unwrap_or(i64::MAX));
+                        assert_eq!(st.max(l, r), a[l..r].iter().copied().max().unwrap_or(i64::MIN));
+                    }
+                }
+            }
+        }
+        let mut st = SegtreeBeats::new(0);
+        assert_eq!(st.sum(0, 0), 0);
+    }
+}
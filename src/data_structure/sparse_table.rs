@@ -0,0 +1,145 @@
+// static range product for idempotent op (min, max, gcd, and, or)
+// O(n log n) build, O(1) query
+pub struct SparseTable<S, F> {
+    n: usize,
+    // table[k][i] = op over [i, i + 2^k)
+    table: Vec<Vec<S>>,
+    op: F,
+}
+
+impl<S, F> SparseTable<S, F>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    pub fn new(v: Vec<S>, op: F) -> Self {
+        let n = v.len();
+        let mut table = vec![v];
+        let mut k = 1;
+        while 1 << k <= n {
+            let prev = &table[k - 1];
+            let row = (0..=n - (1 << k)).map(|i| op(prev[i], prev[i + (1 << (k - 1))])).collect();
+            table.push(row);
+            k += 1;
+        }
+        Self { n, table, op }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    // [left, right) must be non-empty
+    pub fn prod(&self, left: usize, right: usize) -> S {
+        assert!(left < right);
+        assert!(right <= self.n);
+        let k = (usize::BITS - 1 - (right - left).leading_zeros()) as usize;
+        (self.op)(self.table[k][left], self.table[k][right - (1 << k)])
+    }
+}
+
+// static range product for any associative op
+// O(n log n) build, O(1) query
+pub struct DisjointSparseTable<S, F> {
+    n: usize,
+    // on level h the array is cut into blocks of 2^h, each split at its middle m
+    // table[h][i] = op over [i, m) for i < m, op over [m, i] for i >= m
+    table: Vec<Vec<S>>,
+    op: F,
+}
+
+impl<S, F> DisjointSparseTable<S, F>
+where
+    S: Copy,
+    F: Fn(S, S) -> S,
+{
+    pub fn new(v: Vec<S>, op: F) -> Self {
+        let n = v.len();
+        let levels = if n <= 1 {0} else {(usize::BITS - (n - 1).leading_zeros()) as usize};
+        let mut table = vec![v.clone()];
+        for h in 1..=levels {
+            let half = 1 << (h - 1);
+            let mut row = v.clone();
+            for m in (half..n).step_by(2 * half) {
+                for i in (m - half..m - 1).rev() {
+                    row[i] = op(v[i], row[i + 1]);
+                }
+                for i in m + 1..(m + half).min(n) {
+                    row[i] = op(row[i - 1], v[i]);
+                }
+            }
+            table.push(row);
+        }
+        Self { n, table, op }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    // [left, right) must be non-empty
+    pub fn prod(&self, left: usize, right: usize) -> S {
+        assert!(left < right);
+        assert!(right <= self.n);
+        let r = right - 1;
+        if left == r {
+            return self.table[0][left];
+        }
+        let h = (usize::BITS - (left ^ r).leading_zeros()) as usize;
+        (self.op)(self.table[h][left], self.table[h][r])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+    use crate::math::bgcd::bgcd;
+
+    #[test]
+    fn test_sparse_table() {
+        let mut rng = Rng::new();
+        for n in 0..40 {
+            let a = (0..n).map(|_| (rng.next_usize() % 1000) as u64 * [1, 6, 10][rng.next_usize() % 3]).collect::<Vec<u64>>();
+            let min = SparseTable::new(a.clone(), |x: u64, y: u64| x.min(y));
+            let gcd = SparseTable::new(a.clone(), bgcd);
+            let or = SparseTable::new(a.clone(), |x, y| x | y);
+            assert_eq!(min.len(), n);
+            for l in 0..n {
+                for r in l + 1..=n {
+                    assert_eq!(min.prod(l, r), a[l..r].iter().copied().min().unwrap());
+                    assert_eq!(gcd.prod(l, r), a[l..r].iter().fold(0, |g, &x| bgcd(g, x)));
+                    assert_eq!(or.prod(l, r), a[l..r].iter().fold(0, |s, &x| s | x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        const MOD: i64 = 998244353;
+        let mut rng = Rng::new();
+        // x -> a * x + b, op(f, g) applies f first, then g
+        let op = |f: (i64, i64), g: (i64, i64)| (f.0 * g.0 % MOD, (g.0 * f.1 + g.1) % MOD);
+        for n in 0..40 {
+            let a = (0..n).map(|_| ((rng.next_usize() % 100) as i64, (rng.next_usize() % 100) as i64)).collect::<Vec<_>>();
+            let st = DisjointSparseTable::new(a.clone(), op);
+            let sum = DisjointSparseTable::new(a.iter().map(|f| f.0).collect(), |x, y| x + y);
+            assert_eq!(st.len(), n);
+            for l in 0..n {
+                for r in l + 1..=n {
+                    assert_eq!(st.prod(l, r), a[l + 1..r].iter().fold(a[l], |acc, &f| op(acc, f)));
+                    assert_eq!(sum.prod(l, r), a[l..r].iter().map(|f| f.0).sum::<i64>());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use crate::data_structure::bit_vector::BitVector;
+
+// static sequence of u64, queries are O(log σ) where σ is the maximum value
+// levels go from the highest bit down, zeros are stably moved to the front
+pub struct WaveletMatrix {
+    n: usize,
+    log: usize,
+    bits: Vec<BitVector>,
+    // zeros[d]: the number of zeros on level d
+    zeros: Vec<usize>,
+}
+
+impl WaveletMatrix {
+    pub fn new(a: &[u64]) -> Self {
+        let n = a.len();
+        let max = a.iter().copied().max().unwrap_or(0);
+        let log = (64 - max.leading_zeros()) as usize;
+        let mut cur = a.to_vec();
+        let mut bits = Vec::with_capacity(log);
+        let mut zeros = Vec::with_capacity(log);
+        for d in (0..log).rev() {
+            let b = cur.iter().map(|&x| x >> d & 1 == 1).collect::<Vec<bool>>();
+            bits.push(BitVector::new(&b));
+            zeros.push(b.iter().filter(|&&x| !x).count());
+            let (lo, hi): (Vec<u64>, Vec<u64>) = cur.iter().partition(|&&x| x >> d & 1 == 0);
+            cur = lo;
+            cur.extend(hi);
+        }
+        Self { n, log, bits, zeros }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn access(&self, mut i: usize) -> u64 {
+        assert!(i < self.n);
+        let mut res = 0;
+        for d in 0..self.log {
+            let b = self.bits[d].get(i);
+            res = res << 1 | b as u64;
+            i = self.next(d, i, b);
+        }
+        res
+    }
+
+    // the number of occurrences of value in [0, r)
+    pub fn rank(&self, value: u64, r: usize) -> usize {
+        assert!(r <= self.n);
+        if !self.fits(value) {
+            return 0;
+        }
+        let (mut l, mut r) = (0, r);
+        for d in 0..self.log {
+            let b = value >> (self.log - 1 - d) & 1 == 1;
+            l = self.next(d, l, b);
+            r = self.next(d, r, b);
+        }
+        r - l
+    }
+
+    // the k-th (0-indexed) smallest value in [l, r)
+    pub fn kth_smallest(&self, mut l: usize, mut r: usize, mut k: usize) -> u64 {
+        assert!(l <= r && r <= self.n);
+        assert!(k < r - l);
+        let mut res = 0;
+        for d in 0..self.log {
+            let z = self.bits[d].rank0(r) - self.bits[d].rank0(l);
+            let b = k >= z;
+            if b {
+                k -= z;
+            }
+            res = res << 1 | b as u64;
+            l = self.next(d, l, b);
+            r = self.next(d, r, b);
+        }
+        res
+    }
+
+    pub fn kth_largest(&self, l: usize, r: usize, k: usize) -> u64 {
+        assert!(k < r - l);
+        self.kth_smallest(l, r, r - l - 1 - k)
+    }
+
+    // the number of x in [l, r) with lo <= x < hi
+    pub fn range_freq(&self, l: usize, r: usize, lo: u64, hi: u64) -> usize {
+        assert!(l <= r && r <= self.n);
+        if lo >= hi {
+            return 0;
+        }
+        self.count_less(l, r, hi) - self.count_less(l, r, lo)
+    }
+
+    // the largest x in [l, r) with x < upper
+    pub fn prev_value(&self, l: usize, r: usize, upper: u64) -> Option<u64> {
+        assert!(l <= r && r <= self.n);
+        let c = self.count_less(l, r, upper);
+        if c == 0 {None} else {Some(self.kth_smallest(l, r, c - 1))}
+    }
+
+    // the smallest x in [l, r) with x >= lower
+    pub fn next_value(&self, l: usize, r: usize, lower: u64) -> Option<u64> {
+        assert!(l <= r && r <= self.n);
+        let c = self.count_less(l, r, lower);
+        if c == r - l {None} else {Some(self.kth_smallest(l, r, c))}
+    }
+
+    // the number of x in [l, r) with x < upper
+    fn count_less(&self, mut l: usize, mut r: usize, upper: u64) -> usize {
+        if !self.fits(upper) {
+            return r - l;
+        }
+        let mut res = 0;
+        for d in 0..self.log {
+            let b = upper >> (self.log - 1 - d) & 1 == 1;
+            if b {
+                res += self.bits[d].rank0(r) - self.bits[d].rank0(l);
+            }
+            l = self.next(d, l, b);
+            r = self.next(d, r, b);
+        }
+        res
+    }
+
+    // whether x < 2^log
+    fn fits(&self, x: u64) -> bool {
+        self.log == 64 || x >> self.log == 0
+    }
+
+    // position on level d + 1 of the boundary i on level d, following bit b
+    fn next(&self, d: usize, i: usize, b: bool) -> usize {
+        if b {
+            self.zeros[d] + self.bits[d].rank1(i)
+        }
+        else {
+            self.bits[d].rank0(i)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn test_wavelet_matrix() {
+        let mut rng = Rng::new();
+        for (n, m) in [(0, 1), (1, 1), (10, 1), (30, 8), (50, 1000), (40, u64::MAX)] {
+            let a = (0..n).map(|_| if m == u64::MAX {rng.next_u64()} else {rng.next_u64() % m}).collect::<Vec<u64>>();
+            let wm = WaveletMatrix::new(&a);
+            assert_eq!(wm.len(), n);
+            for (i, &x) in a.iter().enumerate() {
+                assert_eq!(wm.access(i), x);
+                assert_eq!(wm.rank(x, i + 1), a[..=i].iter().filter(|&&y| y == x).count());
+            }
+            for _ in 0..300 {
+                let (l, r) = rng.range(n);
+                let pick = |x: u64| if n > 0 && x.is_multiple_of(2) {a[x as usize % n]} else if m == u64::MAX {x} else {x % (m + 2)};
+                let (x, y) = (pick(rng.next_u64()), pick(rng.next_u64()));
+                let (lo, hi) = (x.min(y), x.max(y));
+                let mut b = a[l..r].to_vec();
+                b.sort();
+                assert_eq!(wm.rank(x, r), a[..r].iter().filter(|&&v| v == x).count());
+                assert_eq!(wm.range_freq(l, r, lo, hi), b.iter().filter(|&&v| lo <= v && v < hi).count());
+                assert_eq!(wm.prev_value(l, r, x), b.iter().copied().filter(|&v| v < x).max());
+                assert_eq!(wm.next_value(l, r, x), b.iter().copied().filter(|&v| v >= x).min());
+                if l < r {
+                    let k = rng.next_usize() % (r - l);
+                    assert_eq!(wm.kth_smallest(l, r, k), b[k]);
+                    assert_eq!(wm.kth_largest(l, r, k), b[r - l - 1 - k]);
+                }
+            }
+        }
+    }
+}
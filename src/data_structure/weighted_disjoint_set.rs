@@ -0,0 +1,137 @@
+// potential(y) - potential(x) over an abelian group (op, e, inv)
+pub struct WeightedDisjointSet<T, F, G> {
+    _n: usize,
+    data: Vec<usize>,
+    size: Vec<usize>,
+    pot: Vec<T>,
+    op: F,
+    inv: G,
+}
+
+impl<T, F, G> WeightedDisjointSet<T, F, G>
+where
+    T: Copy + PartialEq,
+    F: Fn(T, T) -> T,
+    G: Fn(T) -> T,
+{
+    pub fn new(n: usize, e: T, op: F, inv: G) -> Self {
+        Self {
+            _n: n,
+            data: (0..n).collect::<Vec<usize>>(),
+            size: vec![1; n],
+            pot: vec![e; n],
+            op,
+            inv,
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        if self.data[x] == x {
+            x
+        }
+        else {
+            let px = self.data[x];
+            let root = self.find(px);
+            self.pot[x] = (self.op)(self.pot[x], self.pot[px]);
+            self.data[x] = root;
+            root
+        }
+    }
+
+    // potential(x) - potential(root(x))
+    pub fn weight(&mut self, x: usize) -> T {
+        self.find(x);
+        self.pot[x]
+    }
+
+    // potential(y) - potential(x) = w
+    // returns false if it contradicts the existing constraints
+    pub fn unite(&mut self, x: usize, y: usize, w: T) -> bool {
+        let mut px = self.find(x);
+        let mut py = self.find(y);
+        let wx = self.pot[x];
+        let wy = self.pot[y];
+        if px == py {
+            return (self.op)(w, wx) == wy;
+        }
+        // potential(py) - potential(px)
+        let mut d = (self.op)((self.op)(w, wx), (self.inv)(wy));
+        if self.size[py] < self.size[px] {
+            std::mem::swap(&mut px, &mut py);
+            d = (self.inv)(d);
+        }
+        self.data[px] = py;
+        self.pot[px] = (self.inv)(d);
+        self.size[py] += self.size[px];
+        self._n -= 1;
+        true
+    }
+
+    // potential(y) - potential(x)
+    pub fn diff(&mut self, x: usize, y: usize) -> Option<T> {
+        if !self.same(x, y) {
+            return None;
+        }
+        Some((self.op)(self.pot[y], (self.inv)(self.pot[x])))
+    }
+
+    pub fn same(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    pub fn num_trees(&self) -> usize {
+        self._n
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let px = self.find(x);
+        self.size[px]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::modint::ModInt;
+
+    #[test]
+    fn weighted_dsu_test() {
+        let mut uf = WeightedDisjointSet::new(5, 0i64, |a, b| a + b, |a: i64| -a);
+        assert!(uf.unite(0, 1, 3));
+        assert!(uf.unite(1, 2, -5));
+        assert!(uf.unite(3, 4, 10));
+        assert_eq!(uf.diff(0, 2), Some(-2));
+        assert_eq!(uf.diff(2, 0), Some(2));
+        assert_eq!(uf.diff(0, 3), None);
+        assert!(uf.unite(2, 4, 1));
+        assert_eq!(uf.diff(0, 3), Some(-11));
+        assert_eq!(uf.diff(4, 1), Some(4));
+        assert!(uf.unite(0, 4, -1));
+        assert!(!uf.unite(0, 4, 0));
+        assert_eq!(uf.num_trees(), 1);
+        assert_eq!(uf.size(3), 5);
+
+        const MOD: usize = 998244353;
+        let mut uf = WeightedDisjointSet::new(3, ModInt::<MOD>::new(0), |a, b| a + b, |a| ModInt::new(0) - a);
+        assert!(uf.unite(0, 1, ModInt::new(MOD - 1)));
+        assert!(uf.unite(1, 2, ModInt::new(5)));
+        assert_eq!(uf.diff(0, 2), Some(ModInt::new(4)));
+        assert!(!uf.unite(2, 0, ModInt::new(4)));
+    }
+
+    #[test]
+    fn bipartite_test() {
+        // odd cycle
+        let edges = [(0, 1), (1, 2), (2, 0)];
+        let mut uf = WeightedDisjointSet::new(3, 0u8, |a, b| a ^ b, |a| a);
+        assert!(!edges.iter().all(|&(u, v)| uf.unite(u, v, 1)));
+
+        // even cycle plus a tail
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 0), (3, 4)];
+        let mut uf = WeightedDisjointSet::new(5, 0u8, |a, b| a ^ b, |a| a);
+        assert!(edges.iter().all(|&(u, v)| uf.unite(u, v, 1)));
+        assert_eq!(uf.diff(0, 2), Some(0));
+        assert_eq!(uf.diff(0, 3), Some(1));
+        assert_eq!(uf.diff(0, 4), Some(0));
+    }
+}
@@ -0,0 +1 @@
+pub mod mst;
\ No newline at end of file
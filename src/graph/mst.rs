@@ -0,0 +1,159 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::iter::Sum;
+use crate::data_structure::disjoint_set::DisjointSet;
+
+// edges: (u, v, weight)
+// returns (total weight, indices of the chosen edges in ascending order)
+// a minimum spanning forest is returned if the graph is disconnected
+
+// O(m log m)
+pub fn kruskal<T>(n: usize, edges: &[(usize, usize, T)]) -> (T, Vec<usize>)
+where
+    T: Copy + Ord + Sum,
+{
+    let mut ord = (0..edges.len()).collect::<Vec<usize>>();
+    ord.sort_by_key(|&i| (edges[i].2, i));
+    let mut uf = DisjointSet::new(n);
+    let mut used = Vec::new();
+    for i in ord {
+        let (u, v, _) = edges[i];
+        if uf.unite(u, v).is_some() {
+            used.push(i);
+        }
+    }
+    finish(edges, used)
+}
+
+// O(m log m)
+pub fn prim<T>(n: usize, edges: &[(usize, usize, T)]) -> (T, Vec<usize>)
+where
+    T: Copy + Ord + Sum,
+{
+    let mut g = vec![Vec::new(); n];
+    for (i, &(u, v, _)) in edges.iter().enumerate() {
+        g[u].push((v, i));
+        g[v].push((u, i));
+    }
+    let mut visited = vec![false; n];
+    let mut used = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for s in 0..n {
+        if visited[s] {
+            continue;
+        }
+        visited[s] = true;
+        for &(to, i) in &g[s] {
+            heap.push(Reverse((edges[i].2, i, to)));
+        }
+        while let Some(Reverse((_, i, v))) = heap.pop() {
+            if visited[v] {
+                continue;
+            }
+            visited[v] = true;
+            used.push(i);
+            for &(to, j) in &g[v] {
+                if !visited[to] {
+                    heap.push(Reverse((edges[j].2, j, to)));
+                }
+            }
+        }
+    }
+    finish(edges, used)
+}
+
+// O(m log n)
+pub fn boruvka<T>(n: usize, edges: &[(usize, usize, T)]) -> (T, Vec<usize>)
+where
+    T: Copy + Ord + Sum,
+{
+    let mut uf = DisjointSet::new(n);
+    let mut used = Vec::new();
+    loop {
+        // cheapest outgoing edge of each component, ties broken by index
+        let mut best: Vec<Option<usize>> = vec![None; n];
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            let (pu, pv) = (uf.find(u), uf.find(v));
+            if pu == pv {
+                continue;
+            }
+            for p in [pu, pv] {
+                if best[p].is_none_or(|j| (w, i) < (edges[j].2, j)) {
+                    best[p] = Some(i);
+                }
+            }
+        }
+        let mut merged = false;
+        for i in best.into_iter().flatten() {
+            let (u, v, _) = edges[i];
+            if uf.unite(u, v).is_some() {
+                used.push(i);
+                merged = true;
+            }
+        }
+        if !merged {
+            break;
+        }
+    }
+    finish(edges, used)
+}
+
+fn finish<T>(edges: &[(usize, usize, T)], mut used: Vec<usize>) -> (T, Vec<usize>)
+where
+    T: Copy + Ord + Sum,
+{
+    used.sort();
+    (used.iter().map(|&i| edges[i].2).sum(), used)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::Rng;
+
+    #[test]
+    fn mst_test() {
+        let edges = vec![
+            (1, 0, 1),
+            (2, 0, 3),
+            (2, 1, 1),
+            (3, 1, 7),
+            (4, 2, 1),
+            (1, 4, 3),
+            (3, 4, 1),
+            (3, 5, 1),
+            (4, 5, 6),
+        ];
+        for f in [kruskal::<i64>, prim::<i64>, boruvka::<i64>] {
+            assert_eq!(f(6, &edges), (5, vec![0, 2, 4, 6, 7]));
+        }
+
+        // disconnected: {0, 1, 2}, {3, 4}, {5}
+        let edges = vec![(0, 1, 4), (3, 4, -2), (1, 2, 3), (2, 0, 1), (4, 3, -5)];
+        for f in [kruskal::<i64>, prim::<i64>, boruvka::<i64>] {
+            assert_eq!(f(6, &edges), (-1, vec![2, 3, 4]));
+        }
+        assert_eq!(kruskal::<i64>(3, &[]), (0, vec![]));
+    }
+
+    #[test]
+    fn mst_random_test() {
+        let mut rng = Rng::new();
+        for _ in 0..100 {
+            let n = rng.next_usize() % 20 + 1;
+            let m = rng.next_usize() % 60;
+            let edges = (0..m).map(|_| (rng.next_usize() % n, rng.next_usize() % n, (rng.next_usize() % 10) as i64)).collect::<Vec<_>>();
+            let (w, used) = kruskal(n, &edges);
+            let mut uf = DisjointSet::new(n);
+            for &(u, v, _) in &edges {
+                uf.unite(u, v);
+            }
+            assert_eq!(used.len(), n - uf.num_trees());
+            for f in [prim::<i64>, boruvka::<i64>] {
+                let (w2, used2) = f(n, &edges);
+                assert_eq!(w, w2);
+                assert_eq!(used2.len(), used.len());
+            }
+        }
+    }
+}
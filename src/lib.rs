@@ -1,6 +1,7 @@
 pub mod math;
 pub mod util;
 pub mod data_structure;
+pub mod graph;
 
 #[cfg(test)]
 mod tests {
@@ -1,5 +1,5 @@
 // 0 <= a, b <= i64::MAX
-fn bgcd(a: u64, b: u64) -> u64 {
+pub fn bgcd(a: u64, b: u64) -> u64 {
     if a == 0 {return b;}
     if b == 0 {return a;}
     let mut a = a as i64;
@@ -1,4 +1,6 @@
 pub mod procon_io;
 pub mod interactive_io;
 pub mod timer;
-pub mod binary_search;
\ No newline at end of file
+pub mod binary_search;
+#[cfg(test)]
+pub mod rng;
@@ -0,0 +1,36 @@
+// xorshift for randomized tests, every test starts from the same seed
+pub struct Rng(u64);
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rng {
+    pub fn new() -> Self {
+        Self(88172645463325252)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 7;
+        self.0 ^= self.0 >> 9;
+        self.0
+    }
+
+    pub fn next_usize(&mut self) -> usize {
+        self.next_u64() as usize
+    }
+
+    // in [lo, hi)
+    pub fn int(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo < hi);
+        lo + (self.next_u64() % (hi - lo) as u64) as i64
+    }
+
+    // 0 <= l <= r <= n
+    pub fn range(&mut self, n: usize) -> (usize, usize) {
+        let (l, r) = (self.next_usize() % (n + 1), self.next_usize() % (n + 1));
+        (l.min(r), l.max(r))
+    }
+}